@@ -0,0 +1,151 @@
+import fs from 'node:fs';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { isMainThread, Worker, workerData, parentPort } from 'node:worker_threads';
+import { createEnginePool, createPool, EnginePool } from '@lojhan/resource-pool';
+
+const ITERATIONS = 1_000_000;
+const THREADS = 4;
+// Regression threshold for --check: a case fails below this fraction of its saved baseline
+const MIN_RATIO = 0.7;
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+const baselineFile = path.join(__dirname, 'hot-paths.baseline.json');
+
+// Contended case: each worker hammers the shared buffer and reports when done
+if (!isMainThread) {
+  const pool = EnginePool.attach(workerData.buffer);
+  for (let i = 0; i < workerData.iterations; i++) {
+    const idx = pool.acquire();
+    if (idx !== -1) pool.release(idx);
+  }
+  parentPort.postMessage('done');
+}
+
+const cases = [
+  {
+    name: 'EnginePool acquire/release',
+    run: () => {
+      const pool = createEnginePool(10);
+      for (let i = 0; i < ITERATIONS; i++) pool.release(pool.acquire());
+      pool.destroy();
+    },
+  },
+  {
+    name: 'ObjectPool acquire/release',
+    run: async () => {
+      const pool = createPool({ min: 10, max: 10, resourceFactory: () => ({}) });
+      for (let i = 0; i < ITERATIONS; i++) pool.release(pool.acquire());
+      await pool.destroy();
+    },
+  },
+  {
+    name: 'EnginePool acquireAsync (uncontended)',
+    run: async () => {
+      const pool = createEnginePool(10);
+      for (let i = 0; i < ITERATIONS; i++) pool.release(await pool.acquireAsync());
+      pool.destroy();
+    },
+  },
+  {
+    name: 'ObjectPool acquireAsync (uncontended)',
+    run: async () => {
+      const pool = createPool({ min: 10, max: 10, resourceFactory: () => ({}) });
+      for (let i = 0; i < ITERATIONS; i++) pool.release(await pool.acquireAsync());
+      await pool.destroy();
+    },
+  },
+  {
+    // Release reads the slot index off the resource, so it costs the same however large the pool is
+    name: 'ObjectPool release, 1000 held (O(1) slot lookup)',
+    run: async () => {
+      const pool = createPool({ min: 1000, max: 1000, resourceFactory: () => ({}) });
+      const held = Array.from({ length: 1000 }, () => pool.acquire());
+      for (let i = 0; i < ITERATIONS; i++) {
+        const res = held[i % 1000];
+        pool.release(res);
+        held[i % 1000] = pool.acquire();
+      }
+      await pool.destroy();
+    },
+  },
+  {
+    // What release would cost if it had to find the resource by scanning, for comparison
+    name: 'Reference: indexOf scan over 1000 resources (O(n))',
+    run: () => {
+      const resources = Array.from({ length: 1000 }, () => ({}));
+      let found = 0;
+      for (let i = 0; i < ITERATIONS; i++) found += resources.indexOf(resources[i % 1000]);
+      return found;
+    },
+  },
+  {
+    name: `EnginePool acquire/release, ${THREADS} threads`,
+    run: async () => {
+      const pool = createEnginePool(THREADS);
+      const iterations = ITERATIONS / THREADS;
+      await Promise.all(
+        Array.from({ length: THREADS }, () => {
+          const worker = new Worker(fileURLToPath(import.meta.url), {
+            workerData: { buffer: pool.getBuffer(), iterations },
+          });
+          return new Promise((resolve, reject) => {
+            worker.once('message', resolve);
+            worker.once('error', reject);
+          });
+        }),
+      );
+      pool.destroy();
+    },
+  },
+];
+
+async function measure({ name, run }) {
+  process.stdout.write(`Running ${name}... `);
+  const start = performance.now();
+  await run();
+  const duration = performance.now() - start;
+  console.log('Done.');
+  return { name, duration, opsPerSec: Math.floor(ITERATIONS / (duration / 1000)) };
+}
+
+async function main() {
+  const save = process.argv.includes('--save');
+  const check = process.argv.includes('--check');
+  const baseline = check && fs.existsSync(baselineFile) ? JSON.parse(fs.readFileSync(baselineFile, 'utf8')) : null;
+  if (check && !baseline) {
+    console.error(`No baseline at ${baselineFile}, run with --save first`);
+    process.exit(1);
+  }
+
+  console.log('=== Hot Path Benchmarks ===\n');
+  console.log(`Iterations: ${new Intl.NumberFormat().format(ITERATIONS)}\n`);
+
+  // Warm up every path so the JIT has compiled them before anything is timed
+  for (const c of cases.slice(0, 5)) await c.run();
+
+  const results = [];
+  for (const c of cases) results.push(await measure(c));
+
+  console.table(
+    results.map((r) => ({
+      Name: r.name,
+      'Duration (ms)': parseFloat(r.duration.toFixed(2)),
+      'Ops/sec': new Intl.NumberFormat().format(r.opsPerSec),
+      'vs baseline': baseline?.[r.name] ? `${((r.opsPerSec / baseline[r.name]) * 100).toFixed(0)}%` : '-',
+    })),
+  );
+
+  if (save) {
+    fs.writeFileSync(baselineFile, JSON.stringify(Object.fromEntries(results.map((r) => [r.name, r.opsPerSec])), null, 2));
+    console.log(`Baseline saved to ${baselineFile}`);
+  }
+
+  if (check) {
+    const regressions = results.filter((r) => baseline[r.name] && r.opsPerSec < baseline[r.name] * MIN_RATIO);
+    for (const r of regressions) console.error(`Regression: ${r.name} dropped below ${MIN_RATIO * 100}% of baseline`);
+    if (regressions.length > 0) process.exit(1);
+  }
+}
+
+if (isMainThread) main();
@@ -1,26 +1,48 @@
 import { BasePool } from './internal/base-pool';
-import type { IObjectPool, PoolConfig, PoolMetrics } from './internal/interfaces';
-import { SLOT_SYMBOL } from './internal/interfaces';
+import { PoolError } from './internal/errors';
+import type {
+  AdmissionState,
+  CheckedOutResource,
+  EvictionStrategy,
+  IObjectPool,
+  PoolConfig,
+  PoolConfigSnapshot,
+  PoolMetrics,
+  PoolObserver,
+  ReleaseOutcome,
+  Reservation,
+  ResourceGuard,
+  TryAcquireResult,
+} from './internal/interfaces';
+import { SLOT_SYMBOL, tagSlot, untagSlot } from './internal/interfaces';
+import { isAcquireTraced, traceAcquire } from './internal/tracing';
 
 const DEFAULT_FACTORY_TIMEOUT_MS = 5000;
 const DEFAULT_DESTROYER_TIMEOUT_MS = 5000;
 const DEFAULT_VALIDATOR_TIMEOUT_MS = 3000;
 
 const DEFAULT_SCALE_DOWN_INTERVAL_MS = 10000;
+const DEFAULT_RESIZE_COOLDOWN_MS = 1000;
 const DEFAULT_IDLE_TIMEOUT_MS = 30000;
 
 export class ObjectPool<T extends object> implements IObjectPool<T> {
+  // Slots are allocated on BasePool's lock-free index stack; resources[idx] is the object for slot idx
   private pool: BasePool;
   private resources: (T | null)[];
   private factory: () => T | Promise<T>;
   private destroyer?: (resource: T) => void | Promise<void>;
   private validator?: (resource: T) => boolean | Promise<boolean>;
+  private releaseTransform?: (resource: T) => T;
+  private observer?: PoolObserver<T>;
 
   private min: number;
   private max: number;
   private idleTimeout: number;
   private scaleInterval: number;
+  private resizeCooldown: number;
   private defaultTimeout: number;
+  private minAcquireDelay: number;
+  private evictionStrategy: EvictionStrategy;
 
   private factoryTimeoutMs: number;
   private destroyerTimeoutMs: number;
@@ -37,6 +59,19 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
   private opCount = 0;
   private isDestroyed = false;
   private scaleDownTimer: NodeJS.Timeout | null = null;
+  private timersRefed = true;
+  private resizeTarget: number | null = null;
+  private resizeTimer: NodeJS.Timeout | null = null;
+  // Label and checkout time of resources acquired through acquireAsyncLabeled(), by slot
+  private holders = new Map<number, { label: string; since: number }>();
+  private reservations = 0;
+  // When the resource in each slot was created (or joined the pool), for replaceExpired()
+  private createdAt: number[];
+  // Logical release time by slot, only kept for evictionStrategy 'oldest'
+  private releasedAt: number[] = [];
+  private releaseClock = 0;
+  // Free slots acquire() passed over because they held no resource, see skippedSlotCount()
+  private skippedSlots = 0;
 
   constructor(basePool: BasePool, resources: (T | null)[], config: PoolConfig<T>, availableIndexes: number[]) {
     this.pool = basePool;
@@ -47,6 +82,8 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
     this.factory = config.resourceFactory;
     this.destroyer = config.resourceDestroyer;
     this.validator = config.validateResource;
+    this.releaseTransform = config.releaseTransform;
+    this.observer = config.observer;
 
     this.bubbleFactoryErrors = config.bubbleFactoryErrors || false;
     this.bubbleDestroyerErrors = config.bubbleDestroyerErrors || false;
@@ -58,30 +95,99 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
 
     this.idleTimeout = config.idleTimeoutMs || DEFAULT_IDLE_TIMEOUT_MS;
     this.scaleInterval = config.scaleDownIntervalMs || DEFAULT_SCALE_DOWN_INTERVAL_MS;
+    this.resizeCooldown = config.resizeCooldownMs ?? DEFAULT_RESIZE_COOLDOWN_MS;
     this.defaultTimeout = config.acquireTimeoutMs || 0;
+    this.minAcquireDelay = config.minAcquireDelayMs || 0;
+    this.evictionStrategy = config.evictionStrategy ?? 'newest';
+    if (this.evictionStrategy === 'oldest') this.releasedAt = new Array<number>(this.max).fill(0);
     this.availableIndexes = availableIndexes;
     this.lastActivity = Date.now();
+    this.createdAt = new Array<number>(this.max).fill(this.lastActivity);
 
     if (this.max > this.min && this.idleTimeout > 0) {
       this.startScaleDownMonitor();
     }
   }
 
+  /**
+   * Synchronous, non-throwing acquire: returns null when nothing is free (tryAcquire() says why).
+   */
   public acquire(): T | null {
     if ((++this.opCount & 0xff) === 0) this.lastActivity = Date.now();
-    const idx = this.pool.acquire();
+
+    const idx = this.acquireIdle();
     if (idx === -1) return null;
-    return this.resources[idx]!;
+    const res = this.resources[idx]!;
+    this.observer?.onAcquire?.(res);
+    return res;
+  }
+
+  /**
+   * Pop a free slot that holds a resource. Slots whose resource was removed after they became
+   * available are skipped (and reclaimed for creation), as long as others are free.
+   */
+  private acquireIdle(): number {
+    for (let attempts = this.pool.availableCount(); attempts >= 0; attempts--) {
+      const idx = this.pool.acquire();
+      if (idx === -1 || this.resources[idx]) return idx;
+      this.skippedSlots++;
+      this.reclaimEmptySlot(idx);
+    }
+    return -1;
+  }
+
+  /**
+   * acquireIdle() for the shrink paths, honouring evictionStrategy: 'oldest' takes the free resource
+   * released longest ago instead of the top of the stack.
+   */
+  private acquireForEviction(): number {
+    if (this.evictionStrategy !== 'oldest') return this.acquireIdle();
+
+    let oldest = -1;
+    for (const idx of this.pool.availableSlots()) {
+      if (!this.resources[idx]) continue;
+      if (oldest === -1 || this.releasedAt[idx] < this.releasedAt[oldest]) oldest = idx;
+    }
+    return oldest === -1 ? -1 : this.pool.acquireFrom([oldest]);
+  }
+
+  /**
+   * Like acquire(), but tells an exhausted pool apart from a destroyed one.
+   */
+  public tryAcquire(): TryAcquireResult<T> {
+    if (this.isDestroyed) return { ok: false, reason: 'closed' };
+    const resource = this.acquire();
+    return resource ? { ok: true, resource } : { ok: false, reason: 'empty' };
   }
 
   public async acquireAsync(timeoutMs?: number): Promise<T> {
+    const traced = isAcquireTraced();
+    if (this.minAcquireDelay <= 0 && !traced) return this.acquireNext(timeoutMs);
+
+    const start = Date.now();
+    try {
+      const res = await this.acquireNext(timeoutMs);
+      // Hold the acquired resource back until the configured floor has elapsed
+      const remaining = this.minAcquireDelay - (Date.now() - start);
+      if (remaining > 0) await new Promise((resolve) => setTimeout(resolve, remaining));
+      if (traced) traceAcquire('ObjectPool', timeoutMs ?? this.defaultTimeout, start);
+      return res;
+    } catch (error) {
+      if (traced) traceAcquire('ObjectPool', timeoutMs ?? this.defaultTimeout, start, error);
+      throw error;
+    }
+  }
+
+  private async acquireNext(timeoutMs?: number): Promise<T> {
     const timeout = timeoutMs ?? this.defaultTimeout;
-    const deadline = timeout > 0 ? Date.now() + timeout : Infinity;
+    const start = Date.now();
+    const deadline = timeout > 0 ? start + timeout : Infinity;
 
     while (true) {
       // Check timeout
       if (timeout > 0 && Date.now() >= deadline) {
-        throw new Error(`Timeout acquiring resource (${timeout}ms)`);
+        this.onTimedOut(timeout, start);
+        throw new PoolError('timeout', `Timeout acquiring resource (${timeout}ms)`, { timeoutMs: timeout });
       }
 
       // Try to acquire or trigger scale up
@@ -93,33 +199,425 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
       // Wait for resource (BasePool has fast path internally)
       if (idx === -1) {
         const remainingTime = timeout > 0 ? deadline - Date.now() : timeout;
-        idx = await this.pool.acquireAsync(remainingTime > 0 ? remainingTime : timeout);
+        try {
+          idx = await this.pool.acquireAsync(remainingTime > 0 ? remainingTime : timeout);
+        } catch (error) {
+          if (!(error instanceof PoolError && error.kind === 'timeout')) throw error;
+          // Report the caller's timeout, not whatever was left of it for this wait
+          this.onTimedOut(timeout, start);
+          throw new PoolError('timeout', `Timeout acquiring resource (${timeout}ms)`, { timeoutMs: timeout });
+        }
       }
 
       if ((++this.opCount & 0xff) === 0) this.lastActivity = Date.now();
 
-      const res = this.resources[idx]!;
+      const res = this.resources[idx];
+      if (!res) {
+        // Resource was removed while the slot was available, try the next one
+        this.reclaimEmptySlot(idx);
+        continue;
+      }
 
       // Validate with timeout
       if (this.validator) {
-        const isValid = await this.validateWithTimeout(res, this.validatorTimeoutMs);
+        let isValid: boolean;
+        try {
+          isValid = await this.validateWithTimeout(res, this.validatorTimeoutMs);
+        } catch (error) {
+          // Bubbled validation error: the resource is untouched, give the slot back
+          this.pool.release(idx);
+          throw error;
+        }
 
         if (!isValid) {
           // Replace invalid resource in the same slot (keeps slot acquired)
-          await this.replaceResourceInSlot(idx);
-          return this.resources[idx]!;
+          try {
+            await this.replaceResourceInSlot(idx);
+          } catch (error) {
+            // The slot is empty now, hand it back for on-demand creation instead of leaking it
+            this.resources[idx] = null;
+            this.availableIndexes.push(idx);
+            throw error;
+          }
+          const replaced = this.resources[idx]!;
+          this.observer?.onAcquire?.(replaced);
+          return replaced;
         }
       }
 
+      this.observer?.onAcquire?.(res);
       return res;
     }
   }
 
+  private onTimedOut(timeout: number, start: number) {
+    this.observer?.onTimeout?.(timeout, { waitedMs: Date.now() - start, pending: this.pool.pendingCount() });
+  }
+
   public release(resource: T) {
+    this.tryRelease(resource);
+  }
+
+  /**
+   * release() that reports what happened to the resource: `accepted` back into the pool, `rejected`
+   * because it doesn't belong to this pool (or was already replaced), or `dropped` because the pool
+   * is destroyed or the resource was already released.
+   */
+  public tryRelease(resource: T): ReleaseOutcome {
+    // Resources checked out during destroy() were already destroyed with the rest, just drop them
+    if (this.isDestroyed) return 'dropped';
+
     // @ts-ignore
-    const idx = resource[SLOT_SYMBOL] as number;
+    const idx = resource[SLOT_SYMBOL] as number | undefined;
+    if (idx === undefined || this.resources[idx] !== resource) return 'rejected';
+
     if ((++this.opCount & 0xff) === 0) this.lastActivity = Date.now();
-    this.pool.release(idx);
+    if (this.holders.size > 0) this.holders.delete(idx);
+    // A double release is dropped below, don't let the transform replace a resource that is already free
+    let rejectedReplacement: Error | null = null;
+    if (this.releaseTransform && this.pool.isCheckedOut(idx)) rejectedReplacement = this.transformInSlot(idx, resource);
+    if (!this.pool.release(idx)) return 'dropped';
+    if (this.releasedAt.length > 0) this.releasedAt[idx] = ++this.releaseClock;
+    this.observer?.onRelease?.(resource);
+    // The original went back in its place; still tell the caller the transform misbehaved
+    if (rejectedReplacement) throw rejectedReplacement;
+    return 'accepted';
+  }
+
+  /**
+   * Run releaseTransform and put its result in the slot. If it throws, the resource is retired like a failed recycle().
+   * A replacement that can't take over the slot (already pooled, frozen, not an object) is refused before anything
+   * changes: the original stays in the slot and the refusal is returned.
+   */
+  private transformInSlot(idx: number, resource: T): Error | null {
+    let next: T;
+    try {
+      next = this.releaseTransform!(resource);
+    } catch (error) {
+      this.retireSlot(idx, resource);
+      throw error;
+    }
+    if (next === resource) return null;
+    if (typeof next !== 'object' || next === null || !Object.isExtensible(next)) {
+      return new Error('releaseTransform() must return an extensible object');
+    }
+    // Taking it would alias two slots (here or in another pool)
+    if (Object.prototype.hasOwnProperty.call(next, SLOT_SYMBOL)) {
+      return new Error('releaseTransform() returned a resource that is already in a pool');
+    }
+
+    untagSlot(resource);
+    tagSlot(next, idx);
+    this.resources[idx] = next;
+    this.createdAt[idx] = Date.now();
+    return null;
+  }
+
+  /**
+   * Take a checked-out resource out of the pool and destroy it in the background; its slot is freed for creation
+   */
+  private retireSlot(idx: number, resource: T) {
+    this.holders.delete(idx);
+    this.resources[idx] = null;
+    untagSlot(resource);
+    this.availableIndexes.push(idx);
+    if (this.destroyer) this.destroyerWithTimeout(resource, this.destroyerTimeoutMs).catch(console.error);
+  }
+
+  /**
+   * First phase of a two-phase acquire: hold a resource without handing it out yet. Reserve from
+   * several pools, then commit() all reservations once every one succeeded or cancel() the ones you got.
+   */
+  public async reserve(timeoutMs?: number): Promise<Reservation<T>> {
+    const res = await this.acquireAsync(timeoutMs);
+    this.reservations++;
+    let settled = false;
+    const settle = () => {
+      if (settled) throw new Error('Reservation already committed or cancelled');
+      settled = true;
+      this.reservations--;
+    };
+
+    return {
+      commit: () => {
+        settle();
+        return res;
+      },
+      cancel: () => {
+        settle();
+        this.release(res);
+      },
+    };
+  }
+
+  /**
+   * Reservations neither committed nor cancelled yet. Their resources already count as busy in getMetrics().
+   */
+  public reservedCount(): number {
+    return this.reservations;
+  }
+
+  /**
+   * acquireAsync() that tags the resource with `label` (e.g. a request id) until it is released,
+   * so inspectCheckedOut() can tell who holds it and for how long.
+   */
+  public async acquireAsyncLabeled(label: string, timeoutMs?: number): Promise<T> {
+    const res = await this.acquireAsync(timeoutMs);
+    // @ts-ignore
+    this.holders.set(res[SLOT_SYMBOL] as number, { label, since: Date.now() });
+    return res;
+  }
+
+  /**
+   * Wait until every resource is back and no acquireAsync() is queued, e.g. before shutdown or
+   * at the end of a test. Rejects with a `timeout` PoolError if the pool is still busy after `timeoutMs`.
+   */
+  public waitUntilIdle(timeoutMs?: number): Promise<void> {
+    return this.pool.waitUntilIdle(() => this.getMetrics().busy === 0 && this.pool.pendingCount() === 0, timeoutMs);
+  }
+
+  /**
+   * Reject every queued acquireAsync() with `reason` for emergency load shedding. The pool stays usable.
+   * @returns How many waiters were rejected
+   */
+  public rejectPending(reason: Error): number {
+    return this.pool.rejectPending(reason);
+  }
+
+  /**
+   * Custom load shedding: `controller` runs before an acquireAsync() would queue and sees the current
+   * metrics; returning false rejects that acquire with a `queueFull` PoolError. Pass null to remove it.
+   */
+  public setAdmissionController(controller: ((state: AdmissionState) => boolean) | null) {
+    if (!controller) return this.pool.setAdmission(null);
+    this.pool.setAdmission(() => {
+      const { available, size } = this.getMetrics();
+      return controller({ available, pending: this.pool.pendingCount(), size });
+    });
+  }
+
+  /**
+   * Live view of every checked-out resource, for leak triage. Label and hold time are only
+   * known for resources acquired through acquireAsyncLabeled(); they are null otherwise.
+   */
+  public inspectCheckedOut(): CheckedOutResource<T>[] {
+    if (this.isDestroyed) return [];
+
+    const now = Date.now();
+    const checkedOut: CheckedOutResource<T>[] = [];
+    for (let index = 0; index < this.resources.length; index++) {
+      const resource = this.resources[index];
+      if (!resource || !this.pool.isCheckedOut(index)) continue;
+      const holder = this.holders.get(index);
+      checkedOut.push({
+        index,
+        heldForMs: holder ? now - holder.since : null,
+        label: holder ? holder.label : null,
+        resource,
+      });
+    }
+    return checkedOut;
+  }
+
+  /**
+   * Reset a resource's state and return it to the pool in one step (e.g. clearing a reusable buffer).
+   * If `reset` throws, the half-reset resource is taken out of the pool and destroyed instead of being
+   * handed out again, and the error is rethrown. Resources from another pool are `rejected` untouched.
+   * @throws PoolError (`notCheckedOut`) if the resource is idle, before `reset` runs
+   */
+  public recycle(resource: T, reset: (resource: T) => void): ReleaseOutcome {
+    if (this.isDestroyed) return 'dropped';
+
+    // @ts-ignore
+    const idx = resource[SLOT_SYMBOL] as number | undefined;
+    if (idx === undefined || this.resources[idx] !== resource) return 'rejected';
+    // An idle resource is still on the free stack: resetting it would race its next owner, and
+    // retiring it would free a slot that is still in rotation
+    if (!this.pool.isCheckedOut(idx)) {
+      throw new PoolError('notCheckedOut', `Slot ${idx} is not checked out`, { slot: idx });
+    }
+
+    try {
+      reset(resource);
+    } catch (error) {
+      this.retireSlot(idx, resource);
+      throw error;
+    }
+    return this.tryRelease(resource);
+  }
+
+  /**
+   * The resource in slot `index`, without acquiring it: it stays in rotation and may be checked out by
+   * someone else at the same time. Only for reads that tolerate that, e.g. inspecting a cached config.
+   */
+  public peek(index: number): T | null {
+    if (this.isDestroyed) return null;
+    return this.resources[index] ?? null;
+  }
+
+  /**
+   * Take the idle resource in slot `index` (see inspectCheckedOut) out of the pool without destroying it,
+   * e.g. to retire a known-bad connection. Returns null if the slot is empty or its resource is checked out.
+   * The slot is freed for on-demand creation.
+   */
+  public removeIndex(index: number): T | null {
+    if (this.isDestroyed) return null;
+    const res = this.resources[index];
+    if (!res || !this.pool.isAvailable(index)) return null;
+
+    if (this.pool.acquireFrom([index]) !== index) return null;
+
+    this.resources[index] = null;
+    this.availableIndexes.push(index);
+    untagSlot(res);
+    return res;
+  }
+
+  /**
+   * Acquire a resource and take it out of the pool for good: the caller owns it from then on and
+   * the pool never destroys it. The emptied slot is refilled on demand (or with add()).
+   */
+  public async takeAsync(timeoutMs?: number): Promise<{ resource: T; index: number }> {
+    const resource = await this.acquireAsync(timeoutMs);
+    // @ts-ignore
+    const index = resource[SLOT_SYMBOL] as number;
+
+    // The slot stays checked out in the BasePool until a new resource is created in it
+    if (this.holders.size > 0) this.holders.delete(index);
+    this.resources[index] = null;
+    this.availableIndexes.push(index);
+    untagSlot(resource);
+    return { resource, index };
+  }
+
+  /**
+   * Swap every idle resource older than `maxAgeMs` for a fresh one from the factory, keeping the pool's
+   * size. Checked-out resources are left alone. The expired resources are not destroyed: they are
+   * returned for the caller to clean up. A replacement that fails to create leaves its slot to be
+   * refilled on demand.
+   */
+  public async replaceExpired(maxAgeMs: number): Promise<T[]> {
+    if (this.isDestroyed) return [];
+
+    const now = Date.now();
+    const expired: { idx: number; resource: T }[] = [];
+    for (const idx of this.pool.availableSlots()) {
+      const resource = this.resources[idx];
+      if (!resource || now - this.createdAt[idx] < maxAgeMs) continue;
+      // Keep the slot checked out while its replacement is created
+      if (this.pool.acquireFrom([idx]) === idx) expired.push({ idx, resource });
+    }
+
+    await Promise.all(
+      expired.map(async ({ idx, resource }) => {
+        this.resources[idx] = null;
+        untagSlot(resource);
+        try {
+          const res = await this.factoryWithTimeout(this.factoryTimeoutMs);
+          if (this.isDestroyed) {
+            if (this.destroyer) await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
+            return;
+          }
+          tagSlot(res, idx);
+          this.resources[idx] = res;
+          this.createdAt[idx] = Date.now();
+          this.pool.release(idx);
+        } catch (error) {
+          this.availableIndexes.push(idx);
+          console.error('Replacing an expired resource failed', error);
+        }
+      }),
+    );
+
+    return expired.map(({ resource }) => resource);
+  }
+
+  /**
+   * Swap the whole resource set for `resources` in one step, e.g. after a config reload. Waits until the
+   * pool is idle (nothing checked out, queued or being created), then installs the new set so no acquire
+   * ever sees a mix of old and new. Resolves with the old resources, which the caller now owns.
+   * @throws PoolError (`timeout`) if the pool didn't go idle within `timeoutMs` (0 = wait forever)
+   */
+  public async replaceAll(resources: T[], timeoutMs?: number): Promise<T[]> {
+    if (resources.length > this.max) {
+      throw new Error(`Cannot install ${resources.length} resources in a pool of max ${this.max}`);
+    }
+    // Everything that could make the install throw is ruled out here, before the pool is touched
+    if (resources.some((res) => typeof res !== 'object' || res === null || !Object.isExtensible(res))) {
+      throw new Error('replaceAll() needs extensible objects');
+    }
+    if (new Set(resources).size !== resources.length) {
+      throw new Error('replaceAll() got the same resource more than once');
+    }
+    if (resources.some((res) => Object.prototype.hasOwnProperty.call(res, SLOT_SYMBOL))) {
+      throw new Error('replaceAll() needs resources that are not in a pool yet');
+    }
+
+    let old: T[] = [];
+    const swap = () => {
+      if (this.isDestroyed) throw new PoolError('closed', 'Pool is destroyed');
+      if (this.getMetrics().busy > 0 || this.pool.pendingCount() > 0 || this.pendingCreates > 0) return false;
+      old = this.installResources(resources);
+      return true;
+    };
+    // The swap runs inside the idle check, so nothing can be acquired between the two
+    await this.pool.waitUntilIdle(swap, timeoutMs);
+    return old;
+  }
+
+  /**
+   * Lay `resources` out from slot 0, like createPool() does, and retire the current set.
+   * Only safe while nothing is checked out or being created. The new state is built before the
+   * stack is touched, so the pool never ends up half-installed.
+   */
+  private installResources(resources: T[]): T[] {
+    const next = new Array<T | null>(this.max).fill(null);
+    resources.forEach((res, idx) => {
+      tagSlot(res, idx);
+      next[idx] = res;
+    });
+
+    let free = this.pool.acquire();
+    while (free !== -1) free = this.pool.acquire();
+    const old = this.resources.filter((r): r is T => r !== null);
+    for (const res of old) untagSlot(res);
+    this.resources = next;
+    this.availableIndexes = [];
+    this.holders.clear();
+
+    const now = Date.now();
+    for (let idx = 0; idx < resources.length; idx++) {
+      this.createdAt[idx] = now;
+      if (this.releasedAt.length > 0) this.releasedAt[idx] = ++this.releaseClock;
+      this.pool.release(idx);
+    }
+    for (let idx = resources.length; idx < this.max; idx++) this.availableIndexes.push(idx);
+    return old;
+  }
+
+  /**
+   * Add an externally created resource to the pool, filling a free slot.
+   * Works on empty pools (min: 0) too. Throws if the pool is already at capacity.
+   */
+  public add(resource: T) {
+    if (this.isDestroyed) {
+      throw new PoolError('closed', 'Pool is destroyed');
+    }
+
+    // Its slot tag would be overwritten and the pool it came from could no longer release it
+    if (Object.prototype.hasOwnProperty.call(resource, SLOT_SYMBOL)) {
+      throw new Error('add() needs a resource that is not in a pool');
+    }
+    const slotIdx = this.availableIndexes.pop();
+    if (slotIdx === undefined) {
+      throw new PoolError('full', `Pool is at capacity (${this.max})`);
+    }
+
+    tagSlot(resource, slotIdx);
+    this.resources[slotIdx] = resource;
+    this.createdAt[slotIdx] = Date.now();
+    if (this.releasedAt.length > 0) this.releasedAt[slotIdx] = ++this.releaseClock;
+    this.pool.release(slotIdx);
   }
 
   public async use<R>(fn: (resource: T) => R | Promise<R>, timeoutMs?: number): Promise<R> {
@@ -131,8 +629,147 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
     }
   }
 
+  /**
+   * Keep an acquired slot without a resource off the stack and make it available for creation
+   */
+  private reclaimEmptySlot(idx: number) {
+    if (!this.isDestroyed && !this.availableIndexes.includes(idx)) {
+      this.availableIndexes.push(idx);
+    }
+  }
+
+  /**
+   * Run `fn` once per input, each call holding its own resource for its duration.
+   * Calls run concurrently up to the pool size; resolves with results in input order.
+   */
+  public async useEach<I, R>(
+    inputs: I[],
+    fn: (resource: T, input: I) => R | Promise<R>,
+    timeoutMs?: number,
+  ): Promise<R[]> {
+    return Promise.all(inputs.map((input) => this.use((resource) => fn(resource, input), timeoutMs)));
+  }
+
+  /**
+   * Endless stream of guards for pipeline processing, never more than `maxConcurrent` of them unreleased at once
+   * (even if the pool is larger). Stops once the pool is destroyed; break out of the loop to stop earlier.
+   */
+  public async *guards(maxConcurrent: number, timeoutMs?: number): AsyncGenerator<ResourceGuard<T>> {
+    if (!Number.isInteger(maxConcurrent) || maxConcurrent < 1) {
+      throw new Error('maxConcurrent must be a positive integer');
+    }
+
+    let outstanding = 0;
+    let wake: () => void = () => {};
+    while (!this.isDestroyed) {
+      while (outstanding >= maxConcurrent) await new Promise<void>((resolve) => (wake = resolve));
+      if (this.isDestroyed) return;
+
+      const resource = await this.acquireAsync(timeoutMs);
+      outstanding++;
+      let released = false;
+      yield {
+        resource,
+        release: () => {
+          if (released) return;
+          released = true;
+          outstanding--;
+          this.release(resource);
+          wake();
+        },
+      };
+    }
+  }
+
+  /**
+   * Stop the pool's background timers (idle scale-down, pending resizes) and queued acquireAsync() calls
+   * from keeping the process alive. Resources are still held strongly and stay acquirable; only process
+   * exit is affected: waiters still queued when the event loop runs dry are rejected instead of hanging.
+   */
+  public unref() {
+    this.timersRefed = false;
+    this.scaleDownTimer?.unref();
+    this.resizeTimer?.unref();
+    this.pool.unref();
+  }
+
+  /**
+   * Undo unref(): background timers and waiters keep the process alive again (the default).
+   */
+  public ref() {
+    this.timersRefed = true;
+    this.scaleDownTimer?.ref();
+    this.resizeTimer?.ref();
+    this.pool.ref();
+  }
+
+  /**
+   * Ask for the pool to hold `target` resources. Requests are coalesced: the latest target is
+   * applied once per cooldown window, so rapid calls don't create and destroy resources back to back.
+   * Shrinking only destroys idle resources.
+   */
+  public requestSize(target: number) {
+    if (this.isDestroyed) {
+      throw new PoolError('closed', 'Pool is destroyed');
+    }
+    if (!Number.isInteger(target) || target < this.min || target > this.max) {
+      throw new Error(`target must be between min (${this.min}) and max (${this.max})`);
+    }
+
+    this.resizeTarget = target;
+    if (this.resizeTimer) return;
+
+    this.resizeTimer = setTimeout(() => this.applyResize(), this.resizeCooldown);
+    if (!this.timersRefed) this.resizeTimer.unref();
+  }
+
+  /**
+   * Target recorded by requestSize() that hasn't been applied yet, or null.
+   */
+  public pendingResizeTarget(): number | null {
+    return this.resizeTarget;
+  }
+
+  private applyResize() {
+    this.resizeTimer = null;
+    const target = this.resizeTarget;
+    this.resizeTarget = null;
+    if (target === null || this.isDestroyed) return;
+
+    const size = this.max - this.availableIndexes.length;
+    for (let i = size; i < target; i++) {
+      this.triggerScaleUp().catch(console.error);
+    }
+    for (let i = target; i < size; i++) {
+      const idx = this.acquireForEviction();
+      if (idx === -1) break;
+      this.destroyResourceInSlot(idx).catch(console.error);
+    }
+  }
+
+  /**
+   * Shed idle resources down to `min` right away, e.g. from a memory-pressure signal. Checked-out resources
+   * are left alone. Destroyers run in the background.
+   * @returns How many resources were removed
+   */
+  public onMemoryPressure(): number {
+    if (this.isDestroyed) return 0;
+
+    // Count live resources rather than free slots: slots being destroyed only rejoin availableIndexes later
+    const excess = this.resources.filter((r) => r !== null).length - this.min;
+    let removed = 0;
+    while (removed < excess) {
+      const idx = this.acquireForEviction();
+      if (idx === -1) break;
+      this.destroyResourceInSlot(idx).catch(console.error);
+      removed++;
+    }
+    return removed;
+  }
+
   private canScaleUp(): boolean {
-    return this.availableIndexes.length > 0 && this.pendingCreates < this.availableIndexes.length;
+    // Each in-flight creation has already claimed its slot, so this alone caps them at max - size
+    return this.availableIndexes.length > 0;
   }
 
   private async triggerScaleUp() {
@@ -145,9 +782,15 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
 
     try {
       const res = await this.factoryWithTimeout(this.factoryTimeoutMs);
-      // @ts-ignore
-      res[SLOT_SYMBOL] = slotIdx;
+      if (this.isDestroyed) {
+        // destroy() already ran, so nobody else will clean this one up
+        if (this.destroyer) await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
+        return;
+      }
+      tagSlot(res, slotIdx);
       this.resources[slotIdx] = res;
+      this.createdAt[slotIdx] = Date.now();
+      if (this.releasedAt.length > 0) this.releasedAt[slotIdx] = ++this.releaseClock;
       this.pool.release(slotIdx);
     } catch (error) {
       this.availableIndexes.push(slotIdx);
@@ -168,6 +811,7 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
         this.checkScaleDown();
         run();
       }, this.scaleInterval);
+      if (!this.timersRefed) this.scaleDownTimer.unref();
     };
     run();
   }
@@ -181,24 +825,30 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
     if (activeCount <= this.min) return;
 
     // Try to grab a resource to kill it
-    const idx = this.pool.acquire();
+    const idx = this.acquireForEviction();
     if (idx !== -1) {
-      this.destroyResourceInSlot(idx);
+      this.destroyResourceInSlot(idx).catch(console.error);
     }
   }
 
   private async destroyResourceInSlot(idx: number) {
+    // Empty the slot first so a destroy() running meanwhile doesn't destroy the resource a second time
     const res = this.resources[idx];
-    if (res && this.destroyer) {
-      await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
-    }
-
     this.resources[idx] = null;
-    this.availableIndexes.push(idx);
+    if (res) untagSlot(res);
+    try {
+      if (res && this.destroyer) {
+        await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
+      }
+    } finally {
+      // The resource is gone either way, a throwing destroyer must not leak its slot
+      this.reclaimEmptySlot(idx);
+    }
   }
 
   private async replaceResourceInSlot(idx: number) {
     const res = this.resources[idx];
+    this.resources[idx] = null;
     if (res && this.destroyer) {
       await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
     }
@@ -206,15 +856,16 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
     // Create new resource in the same slot
     try {
       const newRes = await this.factoryWithTimeout(this.factoryTimeoutMs);
-      Object.defineProperty(newRes, SLOT_SYMBOL, {
-        value: idx,
-        writable: false,
-        enumerable: false,
-      });
+      if (this.isDestroyed) {
+        if (this.destroyer) await this.destroyerWithTimeout(newRes, this.destroyerTimeoutMs);
+        throw new PoolError('closed', 'Pool is destroyed');
+      }
+      tagSlot(newRes, idx);
       this.resources[idx] = newRes;
+      this.createdAt[idx] = Date.now();
     } catch (error) {
       // If factory fails during replacement, re-throw as this is critical
-      // The slot will remain acquired but empty, caller must handle
+      // The slot remains acquired but empty, caller must hand it back
       throw error;
     }
   }
@@ -284,23 +935,194 @@ export class ObjectPool<T extends object> implements IObjectPool<T> {
     }
   }
 
-  public async destroy() {
+  /**
+   * Shut the pool down and destroy every resource it holds, checked out or not.
+   * Resolves with the number of resources that were let go (0 when already destroyed).
+   */
+  public async destroy(): Promise<number> {
+    const resources = this.shutdown();
+    if (this.destroyer) {
+      await Promise.all(resources.map((r) => this.destroyerWithTimeout(r, this.destroyerTimeoutMs)));
+    }
+
+    return resources.length;
+  }
+
+  /**
+   * Synchronous destroy(): runs `finalizer` on every managed resource instead of resourceDestroyer.
+   * A finalizer that throws is logged and the rest still run.
+   * @returns How many resources were finalized
+   */
+  public destroyWith(finalizer: (resource: T) => void): number {
+    const resources = this.shutdown();
+    for (const res of resources) {
+      try {
+        finalizer(res);
+      } catch (error) {
+        console.error('Resource finalizer failed', error);
+      }
+    }
+    return resources.length;
+  }
+
+  /**
+   * Mark the pool destroyed and take every resource it holds, checked out or not. Empty once already destroyed.
+   */
+  private shutdown(): T[] {
+    if (this.isDestroyed) return [];
     this.isDestroyed = true;
     if (this.scaleDownTimer) clearTimeout(this.scaleDownTimer);
+    if (this.resizeTimer) clearTimeout(this.resizeTimer);
     this.pool.destroy();
 
-    if (this.destroyer) {
-      await Promise.all(
-        this.resources
-          .filter((r): r is T => r !== null)
-          .map((r) => this.destroyerWithTimeout(r, this.destroyerTimeoutMs)),
-      );
+    const resources = this.resources.filter((r): r is T => r !== null);
+    for (const res of resources) untagSlot(res);
+    this.resources = [];
+    return resources;
+  }
+
+  /**
+   * Shut the pool down and hand every managed resource back to the caller without destroying it.
+   * Throws if any resource is still checked out or being created.
+   */
+  public intoInner(): T[] {
+    return this.reclaim().filter((r): r is T => r !== null);
+  }
+
+  /**
+   * Shut the pool down like intoInner(), but stream resources to `sink` one at a time instead of
+   * building an array. If `sink` returns false the receiver is treated as closed: delivery stops and
+   * the undelivered resources are destroyed. A `sink` that throws is treated the same way, then its error
+   * is rethrown. Resolves with the number of resources delivered.
+   */
+  public async drainInto(sink: (resource: T) => void | boolean | Promise<void | boolean>): Promise<number> {
+    const slots = this.reclaim();
+
+    let delivered = 0;
+    let idx = 0;
+    try {
+      for (; idx < slots.length; idx++) {
+        const res = slots[idx];
+        if (res === null) continue;
+        if ((await sink(res)) === false) break;
+        delivered++;
+      }
+    } finally {
+      // Nobody owns what the closed (or failed) receiver didn't take, including the one it threw on
+      if (this.destroyer) {
+        for (; idx < slots.length; idx++) {
+          const res = slots[idx];
+          if (res !== null) await this.destroyerWithTimeout(res, this.destroyerTimeoutMs);
+        }
+      }
+    }
+
+    return delivered;
+  }
+
+  /**
+   * Mark the pool destroyed and take its slots, provided nothing is checked out or being created.
+   */
+  private reclaim(): (T | null)[] {
+    if (this.isDestroyed) {
+      throw new PoolError('closed', 'Pool is destroyed');
     }
 
+    const inUse = this.max - this.availableIndexes.length - this.pool.availableCount();
+    if (inUse > 0) {
+      throw new PoolError('inUse', `Cannot reclaim resources while ${inUse} are in use`);
+    }
+
+    this.isDestroyed = true;
+    if (this.scaleDownTimer) clearTimeout(this.scaleDownTimer);
+    if (this.resizeTimer) clearTimeout(this.resizeTimer);
+    this.pool.destroy();
+
+    const slots = this.resources;
+    for (const res of slots) if (res !== null) untagSlot(res);
     this.resources = [];
+    return slots;
+  }
+
+  /**
+   * Call `listener` with the number of idle resources whenever it changes, instead of polling getMetrics().
+   * Returns an unsubscribe function.
+   */
+  public watchAvailable(listener: (available: number) => void): () => void {
+    return this.pool.watchAvailable(listener);
+  }
+
+  public isFair(): boolean {
+    return this.pool.isFair();
+  }
+
+  /**
+   * Releases that were ignored because the resource was already back in the pool (a double release).
+   */
+  public overReleaseCount(): number {
+    return this.pool.overReleaseCount();
+  }
+
+  /**
+   * Free slots an acquisition passed over because they held no resource; it moved on to the next free one.
+   * Non-zero means slots were released without a resource in them.
+   */
+  public skippedSlotCount(): number {
+    return this.skippedSlots;
+  }
+
+  /**
+   * Longest time a successful acquireAsync() waited for a free resource, in microseconds.
+   */
+  public maxWaitMicros(): number {
+    return this.pool.maxWaitMicros();
+  }
+
+  public resetWaitStats() {
+    this.pool.resetWaitStats();
+  }
+
+  /**
+   * Zero the cumulative counters (maxWaitMicros(), overReleaseCount(), skippedSlotCount()) for per-interval
+   * reporting. getMetrics() gauges are unaffected.
+   */
+  public resetStats() {
+    this.pool.resetStats();
+    this.skippedSlots = 0;
+  }
+
+  /**
+   * Effective configuration, with defaults filled in. Functions are reported only as present or absent.
+   */
+  public getConfig(): PoolConfigSnapshot {
+    return {
+      min: this.min,
+      max: this.max,
+      acquireTimeoutMs: this.defaultTimeout,
+      minAcquireDelayMs: this.minAcquireDelay,
+      factoryTimeoutMs: this.factoryTimeoutMs,
+      destroyerTimeoutMs: this.destroyerTimeoutMs,
+      validatorTimeoutMs: this.validatorTimeoutMs,
+      idleTimeoutMs: this.idleTimeout,
+      scaleDownIntervalMs: this.scaleInterval,
+      resizeCooldownMs: this.resizeCooldown,
+      fair: this.pool.isFair(),
+      evictionStrategy: this.evictionStrategy,
+      bubbleFactoryErrors: this.bubbleFactoryErrors,
+      bubbleDestroyerErrors: this.bubbleDestroyerErrors,
+      bubbleValidationErrors: this.bubbleValidationErrors,
+      hasDestroyer: this.destroyer !== undefined,
+      hasValidator: this.validator !== undefined,
+      hasReleaseTransform: this.releaseTransform !== undefined,
+      hasObserver: this.observer !== undefined,
+    };
   }
 
   public getMetrics(): PoolMetrics {
+    if (this.isDestroyed) {
+      return { size: 0, available: 0, busy: 0, capacity: this.max, pendingCreates: this.pendingCreates };
+    }
+
     const available = this.pool.availableCount();
     const active = this.max - this.availableIndexes.length;
     return {
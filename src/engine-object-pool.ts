@@ -1,5 +1,7 @@
-import { BasePool } from './internal/base-pool';
-import type { PoolMetrics } from './internal/interfaces';
+import { BasePool, MAX_CAPACITY } from './internal/base-pool';
+import { PoolError } from './internal/errors';
+import { isAcquireTraced, traceAcquire } from './internal/tracing';
+import type { AdmissionState, EnginePoolOptions, PoolMetrics, PoolObserver } from './internal/interfaces';
 
 /**
  * EnginePool - An index-only pool for advanced use cases
@@ -20,23 +22,98 @@ import type { PoolMetrics } from './internal/interfaces';
 export class EnginePool {
   private pool: BasePool;
   private defaultTimeout: number;
+  private useCounts: Uint32Array;
+  private createdAt: number;
+  private observer?: PoolObserver<number>;
+  // Multi-slot acquisitions take turns so two of them can't each hold half of what the other needs
+  private manyTurn: Promise<unknown> = Promise.resolve();
+  // Session affinity: the slot each key was last handed by acquireForKeyAsync(), and the key each slot
+  // belongs to. A slot keeps one key at most, so both maps are bounded by the pool size
+  private affinity = new Map<string, number>();
+  private slotKeys = new Map<number, string>();
 
-  constructor(size: number) {
-    this.pool = new BasePool(size);
+  /**
+   * Largest capacity a pool can be constructed with
+   */
+  public static get maxAllowedCapacity(): number {
+    return MAX_CAPACITY;
+  }
+
+  /**
+   * Check a capacity without constructing a pool
+   * @throws If the capacity is not an integer between 1 and `maxAllowedCapacity`
+   */
+  public static validateCapacity(size: number): void {
+    BasePool.validateCapacity(size);
+  }
+
+  /**
+   * Attach to the buffer of a pool created in another thread (see `getBuffer`)
+   * Slots are shared: an acquire in one thread is visible to every attached pool, and a slot may be
+   * released through any of them (attaching again on the same thread works the same way)
+   * Waiter queues, wait stats and observers stay per instance
+   * @throws If the buffer is not a valid pool buffer
+   */
+  public static attach(buffer: SharedArrayBuffer, options: EnginePoolOptions = {}): EnginePool {
+    return new EnginePool(buffer, options);
+  }
+
+  /**
+   * Read the layout version of a pool buffer without attaching to it
+   * @throws If the buffer is not a pool buffer
+   */
+  public static bufferVersion(buffer: SharedArrayBuffer): number {
+    return BasePool.bufferVersion(buffer);
+  }
+
+  constructor(sizeOrBuffer: number | SharedArrayBuffer, options: EnginePoolOptions = {}) {
+    this.pool = new BasePool(sizeOrBuffer, options);
     this.defaultTimeout = 0;
+    this.observer = options.observer;
+
+    const size = this.pool.getCapacity();
+    this.useCounts = new Uint32Array(size);
+    this.createdAt = Date.now();
 
-    // Initialize all slots as available
-    for (let i = 0; i < size; i++) {
-      this.pool.release(i);
+    // Initialize all slots as available (an attached buffer keeps its current state)
+    if (typeof sizeOrBuffer === 'number') {
+      for (let i = 0; i < size; i++) {
+        this.pool.release(i);
+      }
     }
   }
 
+  /**
+   * Get the shared buffer backing this pool, to be passed to `EnginePool.attach` in a worker
+   */
+  public getBuffer(): SharedArrayBuffer {
+    return this.pool.getBuffer();
+  }
+
   /**
    * Synchronously acquire an available slot
    * @returns The slot index, or -1 if no slots are available
    */
   public acquire(): number {
-    return this.pool.acquire();
+    const idx = this.pool.acquire();
+    if (idx !== -1) this.onAcquired(idx);
+    return idx;
+  }
+
+  /**
+   * Synchronously acquire up to `max` available slots, appending them to `out`
+   * Lets hot loops reuse one array instead of allocating a batch per call
+   * @returns How many slots were acquired (0 if none are available)
+   */
+  public acquireInto(out: number[], max: number): number {
+    let acquired = 0;
+    while (acquired < max) {
+      const idx = this.acquire();
+      if (idx === -1) break;
+      out.push(idx);
+      acquired++;
+    }
+    return acquired;
   }
 
   /**
@@ -46,15 +123,378 @@ export class EnginePool {
    * @throws If timeout is exceeded
    */
   public async acquireAsync(timeoutMs?: number): Promise<number> {
-    return this.pool.acquireAsync(timeoutMs ?? this.defaultTimeout);
+    const timeout = timeoutMs ?? this.defaultTimeout;
+    const start = Date.now();
+    let idx: number;
+    try {
+      idx = await this.pool.acquireAsync(timeout);
+    } catch (error) {
+      if (error instanceof PoolError && error.kind === 'timeout') this.onTimedOut(timeout, start);
+      if (isAcquireTraced()) traceAcquire('EnginePool', timeout, start, error);
+      throw error;
+    }
+    this.onAcquired(idx);
+    if (isAcquireTraced()) traceAcquire('EnginePool', timeout, start);
+    return idx;
+  }
+
+  /**
+   * Like acquireAsync(), but reports where the caller joined the queue (e.g. to show "3rd in line")
+   * `onEnqueue` is called once, synchronously, with the 1-based position if the caller has to wait;
+   * it isn't called when a slot is free right away
+   * @param onEnqueue - Receives the queue position at enqueue time
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index
+   * @throws If timeout is exceeded
+   */
+  public acquireAsyncWithPosition(onEnqueue: (position: number) => void, timeoutMs?: number): Promise<number> {
+    const before = this.pool.pendingCount();
+    // acquireAsync() queues synchronously, so the count already includes this caller
+    const acquired = this.acquireAsync(timeoutMs);
+    const position = this.pool.pendingCount();
+    if (position > before) onEnqueue(position);
+    return acquired;
+  }
+
+  /**
+   * Like acquireAsync(), but gives up as soon as `signal` aborts (e.g. the request that needed the slot
+   * was cancelled): the waiter leaves the queue and the promise rejects with the signal's reason
+   * @param signal - Abort signal that cancels the wait
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index
+   */
+  public async acquireAsyncAbortable(signal: AbortSignal, timeoutMs?: number): Promise<number> {
+    const timeout = timeoutMs ?? this.defaultTimeout;
+    const start = Date.now();
+    let idx: number;
+    try {
+      idx = await this.pool.acquireAsync(timeout, signal);
+    } catch (error) {
+      if (error instanceof PoolError && error.kind === 'timeout') this.onTimedOut(timeout, start);
+      if (isAcquireTraced()) traceAcquire('EnginePool', timeout, start, error);
+      throw error;
+    }
+    this.onAcquired(idx);
+    if (isAcquireTraced()) traceAcquire('EnginePool', timeout, start);
+    return idx;
+  }
+
+  /**
+   * Asynchronously acquire one of `candidates` (e.g. the slots bound to a shard), waiting until one is free
+   * Targeted waits skip the FIFO queue: in fair mode, releases go to queued acquireAsync() callers first
+   * @param candidates - Slot indices that may be returned
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns One of the candidate indices
+   * @throws If a candidate is out of range, the list is empty, or the timeout is exceeded
+   */
+  public async acquireAnyOfAsync(candidates: number[], timeoutMs?: number): Promise<number> {
+    const timeout = timeoutMs ?? this.defaultTimeout;
+    const start = Date.now();
+    let idx: number;
+    try {
+      idx = await this.pool.acquireFromAsync(candidates, timeout);
+    } catch (error) {
+      if (error instanceof PoolError && error.kind === 'timeout') this.onTimedOut(timeout, start);
+      throw error;
+    }
+    this.onAcquired(idx);
+    return idx;
+  }
+
+  /**
+   * Asynchronously acquire a slot for `key` (e.g. a session id), preferring the slot that key got last time
+   * Falls back to any slot when that one is taken, and remembers the new one. A slot remembers only the
+   * last key it was handed to, so handing it to another key forgets the old mapping
+   * In fair mode the preferred slot isn't taken while acquireAsync() callers are queued
+   * @param key - Logical key to keep sticky
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index
+   * @throws If timeout is exceeded
+   */
+  public async acquireForKeyAsync(key: string, timeoutMs?: number): Promise<number> {
+    const preferred = this.affinity.get(key);
+    // Like acquireAsync()'s fast path: jumping the queue for a sticky slot would starve FIFO waiters
+    if (preferred !== undefined && (!this.pool.isFair() || this.pool.pendingCount() === 0)) {
+      const idx = this.pool.acquireFrom([preferred]);
+      if (idx !== -1) {
+        this.onAcquired(idx);
+        return idx;
+      }
+    }
+
+    const idx = await this.acquireAsync(timeoutMs);
+    this.bindKey(key, idx);
+    return idx;
+  }
+
+  private bindKey(key: string, idx: number) {
+    const previousSlot = this.affinity.get(key);
+    if (previousSlot !== undefined) this.slotKeys.delete(previousSlot);
+    const previousKey = this.slotKeys.get(idx);
+    if (previousKey !== undefined) this.affinity.delete(previousKey);
+    this.affinity.set(key, idx);
+    this.slotKeys.set(idx, key);
+  }
+
+  /**
+   * Acquire a slot, putting the thread to sleep (Atomics.wait) until one is released
+   * Intended for worker threads: on the main thread this blocks the event loop
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index, or -1 if the timeout elapsed
+   */
+  public acquireBlocking(timeoutMs?: number): number {
+    const timeout = timeoutMs ?? this.defaultTimeout;
+    const start = Date.now();
+    const idx = this.pool.acquireBlocking(timeout);
+    if (idx === -1) {
+      this.onTimedOut(timeout, start);
+    } else {
+      this.onAcquired(idx);
+    }
+    return idx;
+  }
+
+  /**
+   * Asynchronously acquire `count` slots at once, e.g. for a heavy item that costs several slots' worth
+   * Either all slots are acquired or none are held when the returned promise rejects
+   * The indices are always distinct: a slot showing up twice means a corrupted buffer and fails the batch
+   * @param count - Number of slots (the item's weight), between 1 and the pool size
+   * @param timeoutMs - Timeout in milliseconds for the whole batch (0 = no timeout)
+   * @returns The acquired slot indices
+   * @throws If timeout is exceeded
+   */
+  public acquireManyAsync(count: number, timeoutMs?: number): Promise<number[]> {
+    if (!Number.isInteger(count) || count < 1 || count > this.size) {
+      return Promise.reject(new Error(`count must be between 1 and ${this.size}`));
+    }
+
+    const timeout = timeoutMs ?? this.defaultTimeout;
+    const deadline = timeout > 0 ? Date.now() + timeout : Infinity;
+    const timeoutError = () =>
+      new PoolError('timeout', `Timeout acquiring resource (${timeout}ms)`, { timeoutMs: timeout });
+    // Set once the batch's turn comes up or its timeout fires while still waiting for it, whichever is first
+    let started = false;
+    let expired = false;
+
+    const turn = this.manyTurn.then(async () => {
+      // Timed out behind an earlier batch: skip without touching the pool
+      if (expired) throw timeoutError();
+      started = true;
+      const acquired = new Set<number>();
+      try {
+        while (acquired.size < count) {
+          const remaining = deadline - Date.now();
+          if (remaining <= 0) throw timeoutError();
+          const idx = await this.acquireAsync(remaining === Infinity ? 0 : remaining);
+          if (acquired.has(idx)) {
+            throw new PoolError('invariant', `Slot ${idx} was handed out twice in one batch`, { slot: idx });
+          }
+          acquired.add(idx);
+        }
+        return [...acquired];
+      } catch (error) {
+        for (const idx of acquired) this.release(idx);
+        if (error instanceof PoolError && error.kind === 'timeout') {
+          // Report the batch's timeout, not whatever was left of it for the last slot
+          throw timeoutError();
+        }
+        throw error;
+      }
+    });
+    this.manyTurn = turn.catch(() => {});
+    if (deadline === Infinity) return turn;
+
+    // The turn may wait on an earlier batch with no timeout; once this batch is running, acquireAsync()
+    // enforces what is left of the deadline
+    return new Promise<number[]>((resolve, reject) => {
+      const timer = setTimeout(() => {
+        if (started) return;
+        expired = true;
+        reject(timeoutError());
+      }, timeout);
+      turn.then(
+        (slots) => {
+          clearTimeout(timer);
+          resolve(slots);
+        },
+        (error) => {
+          clearTimeout(timer);
+          reject(error);
+        },
+      );
+    });
+  }
+
+  /**
+   * Asynchronously acquire a slot along with diagnostic metadata
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index, how many times it has been acquired (including this one), its age, and the
+   * pool pressure right after the acquisition (free slots and waiters still queued) for clients that back off
+   * @throws If timeout is exceeded
+   */
+  public async acquireAsyncMeta(
+    timeoutMs?: number,
+  ): Promise<{ idx: number; useCount: number; ageMs: number; availableAfter: number; pendingAfter: number }> {
+    const idx = await this.acquireAsync(timeoutMs);
+    return {
+      idx,
+      useCount: this.useCounts[idx],
+      ageMs: Date.now() - this.createdAt,
+      availableAfter: this.pool.availableCount(),
+      pendingAfter: this.pool.pendingCount(),
+    };
+  }
+
+  /**
+   * Asynchronously acquire a slot with a soft and a hard timeout
+   * Waits up to `hardMs`, flagging the result when the wait outlasted `softMs`
+   * @param softMs - Wait after which the acquisition is considered slow
+   * @param hardMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot index and whether the soft timeout elapsed
+   * @throws If the hard timeout is exceeded
+   */
+  public async acquireAsyncTiered(softMs: number, hardMs: number): Promise<{ idx: number; softTimedOut: boolean }> {
+    if (hardMs > 0 && softMs > hardMs) {
+      throw new Error('softMs must be <= hardMs');
+    }
+
+    // acquireAsync() already takes a free slot right away, unless that would overtake a fair queue
+    const start = Date.now();
+    const idx = await this.acquireAsync(hardMs);
+    const waited = Date.now() - start;
+    return { idx, softTimedOut: waited > 0 && waited >= softMs };
   }
 
   /**
    * Release a previously acquired slot back to the pool
    * @param idx - The slot index to release
+   * @throws PoolError (`invalidSlot`) if the index is out of range; the pool is left untouched
    */
   public release(idx: number): void {
-    this.pool.release(idx);
+    // Dropped releases (double release, destroyed pool) didn't give anything back
+    if (this.pool.release(idx)) this.observer?.onRelease?.(idx);
+  }
+
+  /**
+   * Release a slot so it is the next one handed out (e.g. a freshly validated connection)
+   * This is what release() does already: the free list is LIFO
+   * @param idx - The slot index to release
+   */
+  public releaseFront(idx: number): void {
+    this.release(idx);
+  }
+
+  /**
+   * Release a slot so it is handed out only after every other free slot (e.g. one that should cool down)
+   * O(free slots), unlike release()
+   * @param idx - The slot index to release
+   */
+  public releaseBack(idx: number): void {
+    if (this.pool.releaseBack(idx)) this.observer?.onRelease?.(idx);
+  }
+
+  /**
+   * Associate a u32 (e.g. a handle or an offset into another buffer) with a slot
+   * Payloads live in the shared buffer, so every attached pool sees them
+   * @throws If the slot is out of range or the value is not a u32
+   */
+  public setPayload(idx: number, value: number): void {
+    this.pool.setPayload(idx, value);
+  }
+
+  /**
+   * Read the u32 payload of a slot (0 until set)
+   * @throws If the slot is out of range
+   */
+  public getPayload(idx: number): number {
+    return this.pool.getPayload(idx);
+  }
+
+  /**
+   * Read the payloads of several slots in one call, e.g. the batch from `acquireManyAsync`
+   * @returns The payloads, in the order of `indices`
+   * @throws If any slot is out of range
+   */
+  public getPayloads(indices: number[]): number[] {
+    return this.pool.getPayloads(indices);
+  }
+
+  /**
+   * Acquire a slot along with a token identifying this checkout, to release it with releaseWithToken()
+   * @returns The slot and its token, or null if no slot is free
+   */
+  public acquireWithToken(): { slot: number; token: number } | null {
+    const slot = this.acquire();
+    return slot === -1 ? null : { slot, token: this.pool.generation(slot) };
+  }
+
+  /**
+   * Asynchronously acquire a slot along with a token identifying this checkout
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @returns The slot and its token
+   * @throws If timeout is exceeded
+   */
+  public async acquireAsyncWithToken(timeoutMs?: number): Promise<{ slot: number; token: number }> {
+    const slot = await this.acquireAsync(timeoutMs);
+    return { slot, token: this.pool.generation(slot) };
+  }
+
+  /**
+   * Release a slot acquired with a token. The slot's generation is bumped on every acquire (on any
+   * thread), so a stale reference from an earlier checkout is rejected instead of releasing the new holder's slot
+   * @param slot - The slot index to release
+   * @param token - The token returned with the slot
+   * @throws PoolError (`notCheckedOut`) if the slot is free or was handed out again since `token` was issued
+   */
+  public releaseWithToken(slot: number, token: number): void {
+    if (this.pool.releaseWithToken(slot, token)) this.observer?.onRelease?.(slot);
+  }
+
+  /**
+   * Release a slot, checking that it is currently checked out
+   * Unlike `release`, a double release or a garbage index throws instead of corrupting the pool
+   * @param idx - The slot index to release
+   * @throws If the index is out of range or the slot is not checked out
+   */
+  public releaseChecked(idx: number): void {
+    if (this.pool.releaseChecked(idx)) this.observer?.onRelease?.(idx);
+  }
+
+  /**
+   * Whether a slot is currently checked out (by any thread sharing the buffer)
+   * @throws If the index is out of range
+   */
+  public isCheckedOut(idx: number): boolean {
+    return this.pool.isCheckedOut(idx);
+  }
+
+  private onAcquired(idx: number) {
+    this.useCounts[idx]++;
+    this.observer?.onAcquire?.(idx);
+  }
+
+  private onTimedOut(timeout: number, start: number) {
+    this.observer?.onTimeout?.(timeout, { waitedMs: Date.now() - start, pending: this.pool.pendingCount() });
+  }
+
+  /**
+   * Fail every acquireAsync() queued on this handle with `reason` without closing the pool (load shedding)
+   * @param reason - Error the queued acquisitions reject with
+   * @returns How many waiters were rejected
+   */
+  public rejectPending(reason: Error): number {
+    return this.pool.rejectPending(reason);
+  }
+
+  /**
+   * Run `controller` before an acquireAsync() joins this handle's queue; returning false rejects it
+   * with a `queueFull` PoolError instead (custom load shedding). Pass null to remove the controller.
+   */
+  public setAdmissionController(controller: ((state: AdmissionState) => boolean) | null): void {
+    if (!controller) return this.pool.setAdmission(null);
+    this.pool.setAdmission(() =>
+      controller({ available: this.pool.availableCount(), pending: this.pool.pendingCount(), size: this.size }),
+    );
   }
 
   /**
@@ -104,6 +544,59 @@ export class EnginePool {
     };
   }
 
+  /**
+   * Releases on this thread that were ignored because the slot was already free (a double release)
+   * A non-zero value points at a caller bug; capacity itself is unaffected
+   */
+  public overReleaseCount(): number {
+    return this.pool.overReleaseCount();
+  }
+
+  /**
+   * Longest time a successful async acquisition on this thread waited for a slot, in microseconds
+   * Acquisitions served without queueing count as 0; timed-out waits are not included
+   */
+  public maxWaitMicros(): number {
+    return this.pool.maxWaitMicros();
+  }
+
+  /**
+   * Reset the wait statistics reported by `maxWaitMicros`
+   */
+  public resetWaitStats(): void {
+    this.pool.resetWaitStats();
+  }
+
+  /**
+   * Reset every cumulative counter (`maxWaitMicros`, `overReleaseCount`), e.g. at the start of a reporting interval
+   * Gauges such as `available` and `inUse` are unaffected
+   */
+  public resetStats(): void {
+    this.pool.resetStats();
+  }
+
+  /**
+   * Let the process exit while acquireAsync() calls are queued; if it runs out of other work,
+   * they are rejected on 'beforeExit' instead of hanging. The pool itself stays open
+   */
+  public unref(): void {
+    this.pool.unref();
+  }
+
+  /**
+   * Undo unref(): queued waiters keep the process alive again (the default)
+   */
+  public ref(): void {
+    this.pool.ref();
+  }
+
+  /**
+   * Whether async waiters are served strictly in arrival order
+   */
+  public isFair(): boolean {
+    return this.pool.isFair();
+  }
+
   /**
    * Get the total number of available slots
    */
@@ -119,11 +612,45 @@ export class EnginePool {
   }
 
   /**
-   * Get the number of pending requests
-   * (EnginePool doesn't track pending requests, returns 0)
+   * Get the number of async acquisitions waiting for a slot on this thread
    */
   public get pendingCount(): number {
-    return 0;
+    return this.pool.pendingCount();
+  }
+
+  /**
+   * Subscribe to changes of the available count instead of polling it
+   * Only acquisitions and releases made through this instance are reported
+   * @param listener - Called synchronously with the new count
+   * @returns A function that unsubscribes the listener
+   */
+  public watchAvailable(listener: (available: number) => void): () => void {
+    return this.pool.watchAvailable(listener);
+  }
+
+  /**
+   * Snapshot of the slots that are currently free, in ascending order (O(size))
+   * Other threads may acquire or release right after it is taken
+   */
+  public availableIndices(): number[] {
+    return this.pool.availableSlots();
+  }
+
+  /**
+   * Bitmap of the slots currently checked out on any thread, one bit per slot (slot i is bit i % 8 of byte i >> 3)
+   * A consistent snapshot, meant for tooling that visualizes allocation
+   * @returns null if other threads kept the free list changing, so no consistent snapshot could be taken; retry later
+   */
+  public slotsInUse(): Uint8Array | null {
+    return this.pool.inUseBitmap();
+  }
+
+  /**
+   * Number of slots actually linked on the free stack, as opposed to the shared counter behind availableCount()
+   * The two only differ when the buffer is corrupted, so a mismatch is a drift symptom
+   */
+  public pooledLength(): number {
+    return this.pool.stackLength();
   }
 
   /**
@@ -137,14 +664,51 @@ export class EnginePool {
    * Get the number of busy slots
    */
   public get numUsed(): number {
-    return this.size - this.available;
+    return this.pool.inUseCount();
+  }
+
+  /**
+   * Get the number of busy slots (alias for numUsed)
+   */
+  public get inUse(): number {
+    return this.pool.inUseCount();
+  }
+
+  /**
+   * Wait until every slot is back and no acquisition is queued on this thread, e.g. before shutdown
+   * or at the end of a test. Releases from other threads are picked up as well
+   * @param timeoutMs - Timeout in milliseconds (0 = no timeout)
+   * @throws If the pool is still busy after the timeout
+   */
+  public waitUntilIdle(timeoutMs?: number): Promise<void> {
+    return this.pool.waitUntilIdle(() => this.pool.inUseCount() === 0 && this.pool.pendingCount() === 0, timeoutMs);
+  }
+
+  /**
+   * Check the shared free list for corruption (bad indices, cycles, count drift)
+   * @returns false if other threads kept the list changing, so the check was inconclusive
+   * @throws PoolError (`corrupted`) with a description of the first problem found
+   */
+  public verify(): boolean {
+    return this.pool.verify();
+  }
+
+  /**
+   * Mark every slot as available again, e.g. after a worker holding slots crashed.
+   * Only call this once no thread still holds a slot: releasing a slot acquired before
+   * the reset would hand it out twice.
+   */
+  public reset(): void {
+    this.pool.reset();
   }
 
   /**
-   * Destroy the pool and clean up resources
+   * Destroy the pool: pending async acquisitions on this thread reject with 'Pool is destroyed'
+   * and no further slots are handed out (the shared stack is emptied for attached pools too)
    */
   public destroy(): void {
-    // BasePool doesn't have explicit cleanup needed,
-    // but we keep this for API consistency
+    this.pool.destroy();
+    this.affinity.clear();
+    this.slotKeys.clear();
   }
 }
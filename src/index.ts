@@ -1,10 +1,29 @@
-import { BasePool } from './internal/base-pool';
+import { BasePool, FORMAT_VERSION, MAX_CAPACITY } from './internal/base-pool';
 import { ObjectPool } from './dynamic-object-pool';
 import { EnginePool } from './engine-object-pool';
-import { SLOT_SYMBOL } from './internal/interfaces';
-import type { IObjectPool, PoolConfig } from './internal/interfaces';
-export type { IObjectPool, PoolConfig, PoolMetrics } from './internal/interfaces';
-export { EnginePool };
+import { PoolError } from './internal/errors';
+import { tagSlot } from './internal/interfaces';
+import { ACQUIRE_CHANNEL } from './internal/tracing';
+import type { EnginePoolOptions, EvictionStrategy, IObjectPool, PoolConfig, PoolObserver } from './internal/interfaces';
+export type {
+  AdmissionState,
+  CheckedOutResource,
+  EnginePoolOptions,
+  EvictionStrategy,
+  IObjectPool,
+  PoolConfig,
+  PoolConfigSnapshot,
+  PoolMetrics,
+  PoolObserver,
+  ReleaseOutcome,
+  Reservation,
+  ResourceGuard,
+  TimeoutInfo,
+  TryAcquireResult,
+} from './internal/interfaces';
+export type { PoolErrorKind } from './internal/errors';
+export type { AcquireTrace } from './internal/tracing';
+export { ACQUIRE_CHANNEL, EnginePool, FORMAT_VERSION, MAX_CAPACITY, PoolError };
 
 /**
  * Create an object pool with an async resource factory.
@@ -21,9 +40,15 @@ export function createPool<T extends object>(
     resourceFactory: () => Promise<T>;
     resourceDestroyer?: (resource: T) => void | Promise<void>;
     validateResource?: (resource: T) => boolean | Promise<boolean>;
+    releaseTransform?: (resource: T) => T;
     idleTimeoutMs?: number;
     scaleDownIntervalMs?: number;
+    resizeCooldownMs?: number;
     acquireTimeoutMs?: number;
+    minAcquireDelayMs?: number;
+    fair?: boolean;
+    evictionStrategy?: EvictionStrategy;
+    observer?: PoolObserver<T>;
   },
   initialResources?: T[],
 ): IObjectPool<T>;
@@ -55,9 +80,15 @@ export function createPool<T extends object>(
     resourceFactory: () => T;
     resourceDestroyer?: (resource: T) => void | Promise<void>;
     validateResource?: (resource: T) => boolean | Promise<boolean>;
+    releaseTransform?: (resource: T) => T;
     idleTimeoutMs?: number;
     scaleDownIntervalMs?: number;
+    resizeCooldownMs?: number;
     acquireTimeoutMs?: number;
+    minAcquireDelayMs?: number;
+    fair?: boolean;
+    evictionStrategy?: EvictionStrategy;
+    observer?: PoolObserver<T>;
   },
   initialResources?: T[],
 ): IObjectPool<T>;
@@ -70,6 +101,7 @@ export function createPool<T extends object>(
     resourceFactory: (() => T) | (() => Promise<T>);
     resourceDestroyer?: (resource: T) => void | Promise<void>;
     validateResource?: (resource: T) => boolean | Promise<boolean>;
+    releaseTransform?: (resource: T) => T;
 
     factoryTimeoutMs?: number;
     destroyerTimeoutMs?: number;
@@ -81,7 +113,12 @@ export function createPool<T extends object>(
 
     idleTimeoutMs?: number;
     scaleDownIntervalMs?: number;
+    resizeCooldownMs?: number;
     acquireTimeoutMs?: number;
+    minAcquireDelayMs?: number;
+    fair?: boolean;
+    evictionStrategy?: EvictionStrategy;
+    observer?: PoolObserver<T>;
   },
   initialResources?: T[],
 ): IObjectPool<T> {
@@ -93,7 +130,6 @@ export function createPool<T extends object>(
     throw new Error('resourceFactory is required');
   }
 
-  const INT32_MAX = 2147483647;
   let min = config.min;
   let max = config.max;
 
@@ -128,9 +164,9 @@ export function createPool<T extends object>(
     throw new Error('max must be at least 1');
   }
 
-  // Rule: max cannot exceed INT32_MAX
-  if (max > INT32_MAX) {
-    throw new Error(`max cannot exceed ${INT32_MAX}`);
+  // Rule: max cannot exceed MAX_CAPACITY (65535)
+  if (max > MAX_CAPACITY) {
+    throw new Error(`max cannot exceed ${MAX_CAPACITY}`);
   }
 
   // Rule: max must be >= min
@@ -156,18 +192,14 @@ export function createPool<T extends object>(
 }
 
 function createPoolInternal<T extends object>(config: PoolConfig<T>, initialResources: T[]) {
-  const basePool = new BasePool(config.max);
+  const basePool = new BasePool(config.max, { fair: config.fair });
   const resources = new Array<T | null>(config.max).fill(null);
   const destroyedIndices: number[] = [];
 
   // Place provided resources
   let nextSlot = 0;
   for (let i = 0; i < Math.min(initialResources.length, config.max); i++) {
-    Object.defineProperty(initialResources[i], SLOT_SYMBOL, {
-      value: i,
-      writable: false,
-      enumerable: false,
-    });
+    tagSlot(initialResources[i], i);
     resources[i] = initialResources[i];
     basePool.release(i);
     nextSlot = i + 1;
@@ -181,11 +213,7 @@ function createPoolInternal<T extends object>(config: PoolConfig<T>, initialReso
     const syncFactory = factory as () => T;
     for (let i = nextSlot; i < config.min; i++) {
       const resource = syncFactory();
-      Object.defineProperty(resource, SLOT_SYMBOL, {
-        value: i,
-        writable: false,
-        enumerable: false,
-      });
+      tagSlot(resource, i);
       resources[i] = resource;
       basePool.release(i);
       nextSlot = i + 1;
@@ -203,8 +231,157 @@ function createPoolInternal<T extends object>(config: PoolConfig<T>, initialReso
 /**
  * Create an EnginePool for index-based resource management
  * @param size - The number of slots in the pool
+ * @param options - Optional pool behaviour (e.g. fairness)
  * @returns A new EnginePool instance
  */
-export function createEnginePool(size: number): EnginePool {
-  return new EnginePool(size);
+export function createEnginePool(size: number, options?: EnginePoolOptions): EnginePool {
+  return new EnginePool(size, options);
+}
+
+/**
+ * Recommend a pool size using Little's Law (L = λ·W)
+ * @param throughput - Target acquisitions per second (λ)
+ * @param avgHoldMs - Average time a resource is held, in milliseconds (W)
+ * @returns The number of resources needed to sustain the throughput, rounded up (at least 1)
+ *
+ * @example
+ * ```ts
+ * suggestSize(100, 50); // 100 req/s held for 50ms each -> 5
+ * ```
+ */
+export function suggestSize(throughput: number, avgHoldMs: number): number {
+  if (!Number.isFinite(throughput) || throughput < 0) {
+    throw new Error('throughput must be a non-negative number');
+  }
+  if (!Number.isFinite(avgHoldMs) || avgHoldMs < 0) {
+    throw new Error('avgHoldMs must be a non-negative number');
+  }
+
+  return Math.max(1, Math.ceil((throughput * avgHoldMs) / 1000));
+}
+
+/**
+ * Chainable alternative to the createPool() config object
+ *
+ * @example
+ * ```ts
+ * const pool = new PoolBuilder<Connection>()
+ *   .size(2, 10)
+ *   .factory(async () => connect())
+ *   .validate((conn) => conn.isAlive())
+ *   .acquireTimeout(5000)
+ *   .build();
+ * ```
+ */
+export class PoolBuilder<T extends object> {
+  private config: Partial<PoolConfig<T>> = {};
+  private resources: T[] = [];
+
+  public size(min: number, max: number): this {
+    this.config.min = min;
+    this.config.max = max;
+    return this;
+  }
+
+  public factory(resourceFactory: (() => T) | (() => Promise<T>)): this {
+    this.config.resourceFactory = resourceFactory;
+    return this;
+  }
+
+  public destroyer(resourceDestroyer: (resource: T) => void | Promise<void>): this {
+    this.config.resourceDestroyer = resourceDestroyer;
+    return this;
+  }
+
+  public validate(validateResource: (resource: T) => boolean | Promise<boolean>): this {
+    this.config.validateResource = validateResource;
+    return this;
+  }
+
+  public releaseTransform(releaseTransform: (resource: T) => T): this {
+    this.config.releaseTransform = releaseTransform;
+    return this;
+  }
+
+  public acquireTimeout(ms: number): this {
+    this.config.acquireTimeoutMs = ms;
+    return this;
+  }
+
+  public factoryTimeout(ms: number): this {
+    this.config.factoryTimeoutMs = ms;
+    return this;
+  }
+
+  public destroyerTimeout(ms: number): this {
+    this.config.destroyerTimeoutMs = ms;
+    return this;
+  }
+
+  public validatorTimeout(ms: number): this {
+    this.config.validatorTimeoutMs = ms;
+    return this;
+  }
+
+  public bubbleFactoryErrors(bubble = true): this {
+    this.config.bubbleFactoryErrors = bubble;
+    return this;
+  }
+
+  public bubbleDestroyerErrors(bubble = true): this {
+    this.config.bubbleDestroyerErrors = bubble;
+    return this;
+  }
+
+  public bubbleValidationErrors(bubble = true): this {
+    this.config.bubbleValidationErrors = bubble;
+    return this;
+  }
+
+  public idleTimeout(ms: number): this {
+    this.config.idleTimeoutMs = ms;
+    return this;
+  }
+
+  public scaleDownInterval(ms: number): this {
+    this.config.scaleDownIntervalMs = ms;
+    return this;
+  }
+
+  public resizeCooldown(ms: number): this {
+    this.config.resizeCooldownMs = ms;
+    return this;
+  }
+
+  public minAcquireDelay(ms: number): this {
+    this.config.minAcquireDelayMs = ms;
+    return this;
+  }
+
+  public evictionStrategy(strategy: EvictionStrategy): this {
+    this.config.evictionStrategy = strategy;
+    return this;
+  }
+
+  public fair(fair: boolean): this {
+    this.config.fair = fair;
+    return this;
+  }
+
+  public observer(observer: PoolObserver<T>): this {
+    this.config.observer = observer;
+    return this;
+  }
+
+  public initialResources(resources: T[]): this {
+    this.resources = resources;
+    return this;
+  }
+
+  /**
+   * Create the pool; applies the same validation as createPool()
+   */
+  public build(): IObjectPool<T> {
+    return createPool(this.config as Parameters<typeof createPool<T>>[0], this.resources);
+  }
 }
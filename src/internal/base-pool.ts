@@ -1,34 +1,124 @@
-const OFFSET_LOCK = 0;
-const OFFSET_COUNT = 1;
-const OFFSET_NOTIFY = 2;
-const OFFSET_STACK_START = 3;
+import { PoolError } from './errors';
+import type { EnginePoolOptions } from './interfaces';
 
-// 0 = Unlocked, 1 = Locked
-const LOCK_UNLOCKED = 0;
-const LOCK_LOCKED = 1;
+// Header words of the shared buffer, exported for tests that inspect or corrupt it
+export const OFFSET_MAGIC = 0;
+export const OFFSET_VERSION = 1;
+export const OFFSET_CAPACITY = 2;
+export const OFFSET_HEAD = 3;
+export const OFFSET_COUNT = 4;
+export const OFFSET_NOTIFY = 5;
+export const OFFSET_NEXT_START = 6;
+// Int32 regions per slot after the header: NEXT, PAYLOAD, SLOT_STATE, GENERATION
+const SLOT_REGIONS = 4;
+
+// Marks a buffer as laid out by BasePool ('RPOL')
+export const MAGIC_VALUE = 0x52504f4c;
+// Bump whenever the buffer layout changes
+export const FORMAT_VERSION = 6;
+
+// Per-slot state: FREE slots have never been seeded (or were taken out of rotation), AVAILABLE ones are on the stack
+const SLOT_FREE = 0;
+const SLOT_CHECKED_OUT = 1;
+const SLOT_AVAILABLE = 2;
+
+// HEAD keeps at least this many bits for the ABA tag, so it takes 2^16 pushes/pops between a thread's
+// read and its CAS before a stale head can match again
+const MIN_TAG_BITS = 16;
+// Largest SharedArrayBuffer we count on an engine allocating
+const MAX_BUFFER_BYTES = 2 ** 30;
+// Bounded by the index bits left over (which must also fit the EMPTY marker, all index bits set)
+// and by the buffer the slots have to fit in
+export const MAX_CAPACITY = Math.min(
+  2 ** (32 - MIN_TAG_BITS) - 1,
+  Math.floor((MAX_BUFFER_BYTES / Int32Array.BYTES_PER_ELEMENT - OFFSET_NEXT_START) / SLOT_REGIONS),
+);
+
+type Waiter = {
+  resolve: (handle: number) => void;
+  reject: (error: Error) => void;
+  timer: NodeJS.Timeout | null;
+  // Removes the AbortSignal listener, if the waiter was given a signal
+  detach: (() => void) | null;
+  queuedAt: number;
+};
 
 export class BasePool {
   private buffer: SharedArrayBuffer;
   private state: Int32Array;
   private capacity: number;
-  private stackOffset: number;
+  private nextOffset: number;
+  private payloadOffset: number;
+  private slotStateOffset: number;
+  private generationOffset: number;
+  private fair: boolean;
+  private checkInvariants: boolean;
+
+  // Every shared word is read and written through Atomics, which are sequentially consistent: a count
+  // or slot state read in one thread reflects every push/pop another thread completed before it
+  // HEAD packs [tag | index]: the low bits hold the top slot, the rest a counter bumped on every
+  // push/pop so a CAS never succeeds against a head that was popped and pushed back (ABA)
+  private indexBits: number;
+  private indexMask: number;
+
+  // Local FIFO of async waiters (this thread only)
+  private waiters: Waiter[] = [];
+  private watching = false;
+  private keepAlive: NodeJS.Timeout | null = null;
+  // After unref(), queued waiters don't hold the process open; they are rejected once the process is about
+  // to exit. The pool itself stays open: closing it here would skip the owner's destroy path
+  private refed = true;
+  private readonly onBeforeExit = () => {
+    this.rejectWaiters(new PoolError('closed', 'Pool is closed: the process is exiting'));
+  };
+  // Longest time a successful async acquire spent queued, in microseconds
+  private maxWaitUs = 0;
+  // Releases dropped because the slot was already back on the stack (a caller releasing twice)
+  private overReleases = 0;
+  // watchAvailable() subscribers and the count they last saw
+  private availableWatchers: ((available: number) => void)[] = [];
+  private lastAvailable = 0;
+  // Asked before an async acquire is queued, see setAdmission()
+  private admit: (() => boolean) | null = null;
+  private closed = false;
+
+  constructor(capacityOrBuffer: number | SharedArrayBuffer, options: EnginePoolOptions = {}) {
+    this.fair = options.fair ?? true;
+    this.checkInvariants = options.checkInvariants ?? false;
 
-  constructor(capacityOrBuffer: number | SharedArrayBuffer) {
     if (capacityOrBuffer instanceof SharedArrayBuffer) {
+      this.capacity = BasePool.validateBuffer(capacityOrBuffer);
       this.buffer = capacityOrBuffer;
       this.state = new Int32Array(this.buffer);
-      this.capacity = this.state.length - OFFSET_STACK_START;
-      this.stackOffset = OFFSET_STACK_START;
+      this.nextOffset = OFFSET_NEXT_START;
+      this.payloadOffset = OFFSET_NEXT_START + this.capacity;
+      this.slotStateOffset = OFFSET_NEXT_START + this.capacity * 2;
+      this.generationOffset = OFFSET_NEXT_START + this.capacity * 3;
+      this.indexBits = 32 - Math.clz32(this.capacity);
+      this.indexMask = 2 ** this.indexBits - 1;
     } else {
+      BasePool.validateCapacity(capacityOrBuffer);
       this.capacity = capacityOrBuffer;
-      // Layout: [LOCK, COUNT, NOTIFY, ...STACK_INDICES]
-      const totalInt32s = OFFSET_STACK_START + this.capacity;
+      // Layout: [MAGIC, VERSION, CAPACITY, HEAD, COUNT, NOTIFY, ...NEXT, ...PAYLOAD, ...SLOT_STATE, ...GENERATION]
+      // NEXT[i] links slot i to the slot below it on the free stack, PAYLOAD[i] is user data for slot i,
+      // SLOT_STATE[i] records whether slot i is checked out, back on the stack, or not in rotation yet,
+      // GENERATION[i] is bumped every time slot i is handed out, so a token from an earlier checkout goes stale
+      const totalInt32s = OFFSET_NEXT_START + this.capacity * SLOT_REGIONS;
       this.buffer = new SharedArrayBuffer(totalInt32s * 4);
       this.state = new Int32Array(this.buffer);
-      this.stackOffset = OFFSET_STACK_START;
+      this.nextOffset = OFFSET_NEXT_START;
+      this.payloadOffset = OFFSET_NEXT_START + this.capacity;
+      this.slotStateOffset = OFFSET_NEXT_START + this.capacity * 2;
+      this.generationOffset = OFFSET_NEXT_START + this.capacity * 3;
+      // Smallest index width that still leaves room for the EMPTY marker (all index bits set)
+      this.indexBits = 32 - Math.clz32(this.capacity);
+      this.indexMask = 2 ** this.indexBits - 1;
 
       // Initialize header
-      Atomics.store(this.state, OFFSET_LOCK, LOCK_UNLOCKED);
+      Atomics.store(this.state, OFFSET_MAGIC, MAGIC_VALUE);
+      Atomics.store(this.state, OFFSET_VERSION, FORMAT_VERSION);
+      Atomics.store(this.state, OFFSET_CAPACITY, this.capacity);
+      Atomics.store(this.state, OFFSET_HEAD, this.indexMask); // Empty stack, tag 0
       Atomics.store(this.state, OFFSET_COUNT, 0); // Start with 0 available
       Atomics.store(this.state, OFFSET_NOTIFY, 0);
 
@@ -38,130 +128,818 @@ export class BasePool {
     }
   }
 
+  /**
+   * Throws if `capacity` cannot back a pool. Lets callers pre-validate before constructing.
+   */
+  public static validateCapacity(capacity: number): void {
+    if (!Number.isInteger(capacity)) {
+      throw new Error(`capacity must be an integer, got ${capacity}`);
+    }
+    if (capacity < 1) {
+      throw new Error('capacity must be at least 1');
+    }
+    if (capacity > MAX_CAPACITY) {
+      throw new Error(`capacity cannot exceed ${MAX_CAPACITY}`);
+    }
+  }
+
+  /**
+   * Attach to a buffer created by another BasePool (typically in another thread).
+   * Throws if the buffer doesn't carry a valid BasePool header.
+   */
+  public static attach(buffer: SharedArrayBuffer, options: EnginePoolOptions = {}): BasePool {
+    return new BasePool(buffer, options);
+  }
+
+  /**
+   * Read the layout version of a pool buffer without attaching to it.
+   * Throws if the buffer isn't a pool buffer at all.
+   */
+  public static bufferVersion(buffer: SharedArrayBuffer): number {
+    if (buffer.byteLength % 4 !== 0 || buffer.byteLength < OFFSET_NEXT_START * 4) {
+      throw new Error(`Invalid pool buffer: unexpected size (${buffer.byteLength} bytes)`);
+    }
+
+    const state = new Int32Array(buffer);
+    if (Atomics.load(state, OFFSET_MAGIC) !== MAGIC_VALUE) {
+      throw new Error('Invalid pool buffer: magic value mismatch');
+    }
+
+    return Atomics.load(state, OFFSET_VERSION);
+  }
+
+  /**
+   * Check the header of a shared buffer and return the capacity it was created with.
+   */
+  private static validateBuffer(buffer: SharedArrayBuffer): number {
+    const version = BasePool.bufferVersion(buffer);
+    if (version !== FORMAT_VERSION) {
+      throw new Error(`Unsupported pool buffer version ${version} (expected ${FORMAT_VERSION})`);
+    }
+
+    const state = new Int32Array(buffer);
+    const capacity = Atomics.load(state, OFFSET_CAPACITY);
+    if (capacity < 1 || capacity > MAX_CAPACITY || capacity * SLOT_REGIONS !== state.length - OFFSET_NEXT_START) {
+      throw new Error(`Invalid pool buffer: capacity ${capacity} does not match buffer size`);
+    }
+
+    return capacity;
+  }
+
   public getBuffer() {
     return this.buffer;
   }
 
   /**
-   * O(1) Acquire using a LIFO stack protected by a spinlock.
+   * O(1) lock-free Acquire: pops the top of a Treiber stack with a CAS on the tagged head.
    */
   public acquire(): number {
+    const idx = this.pop();
+    if (idx === -1) return -1;
+    this.checkOut(idx);
+    if (this.availableWatchers.length > 0) this.emitAvailable();
+    return idx;
+  }
+
+  /**
+   * Take the top slot off the stack, leaving its state AVAILABLE: the caller either checks it out or pushes it back
+   */
+  private pop(): number {
     const state = this.state;
+    const bits = this.indexBits;
+    const mask = this.indexMask;
 
-    // 1. Try to lock
-    // Fast path: CAS 0 -> 1
-    let locked = Atomics.compareExchange(state, OFFSET_LOCK, LOCK_UNLOCKED, LOCK_LOCKED) === LOCK_UNLOCKED;
+    while (true) {
+      const head = Atomics.load(state, OFFSET_HEAD);
+      const idx = head & mask;
+      if (idx === mask) return -1;
 
-    if (!locked) {
-      // Spin loop (wait for lock)
-      while (true) {
-        if (Atomics.load(state, OFFSET_LOCK) === LOCK_UNLOCKED) {
-          if (Atomics.compareExchange(state, OFFSET_LOCK, LOCK_UNLOCKED, LOCK_LOCKED) === LOCK_UNLOCKED) {
-            locked = true;
-            break;
-          }
+      // NEXT[idx] may be stale if another thread popped idx meanwhile; the tag makes that CAS fail
+      const next = Atomics.load(state, this.nextOffset + idx);
+      const newHead = (((head >>> bits) + 1) << bits) | next;
+      if (Atomics.compareExchange(state, OFFSET_HEAD, head, newHead) === head) {
+        Atomics.sub(state, OFFSET_COUNT, 1);
+        return idx;
+      }
+    }
+  }
+
+  private checkOut(idx: number) {
+    Atomics.store(this.state, this.slotStateOffset + idx, SLOT_CHECKED_OUT);
+    Atomics.add(this.state, this.generationOffset + idx, 1);
+  }
+
+  /**
+   * Tell watchers, other threads and local waiters that `pushed` slots went onto the stack
+   */
+  private announce(pushed: number) {
+    if (this.availableWatchers.length > 0) this.emitAvailable();
+
+    // Notify waiters on other threads
+    Atomics.add(this.state, OFFSET_NOTIFY, 1);
+    Atomics.notify(this.state, OFFSET_NOTIFY, pushed);
+
+    // Unfair mode: let the oldest waiter race for the slot on the next tick
+    if (this.waiters.length > 0) {
+      queueMicrotask(() => this.serveWaiters());
+    }
+  }
+
+  /**
+   * O(1) lock-free Release.
+   * Releasing a slot that is already on the stack is dropped (and counted in overReleaseCount()):
+   * pushing it a second time would hand the same slot to two owners. Releases after destroy() are dropped too.
+   * @returns false if the release was dropped
+   * @throws PoolError (`invalidSlot`) if the handle is out of range, before anything is touched
+   */
+  public release(handle: number): boolean {
+    this.checkSlot(handle);
+    // Slots still out when this instance was destroyed must not come back to life
+    if (this.closed) return false;
+    const state = this.state;
+    const handOff = this.fair && this.waiters.length > 0;
+
+    // Claim the slot's state first so two racing releases can't both push it
+    const flag = this.slotStateOffset + handle;
+    const prev = Atomics.load(state, flag);
+    const next = handOff ? SLOT_CHECKED_OUT : SLOT_AVAILABLE;
+    if (prev === SLOT_AVAILABLE || Atomics.compareExchange(state, flag, prev, next) !== prev) {
+      this.overReleases++;
+      return false;
+    }
+
+    // Fair mode: hand the slot straight to the oldest waiter so nobody can overtake it
+    if (handOff) {
+      this.handOff(handle);
+      return true;
+    }
+
+    this.push(handle);
+    this.announce(1);
+    return true;
+  }
+
+  /**
+   * release() onto the bottom of the free stack, so the slot is handed out after every other free one.
+   * O(free slots): the stack is lifted off and put back on top, other threads may briefly see it empty.
+   * The lifted slots stay AVAILABLE throughout and nobody is notified until the stack is restored.
+   * @returns false if the release was dropped
+   */
+  public releaseBack(handle: number): boolean {
+    this.checkSlot(handle);
+    if (this.closed) return false;
+    // Fair hand-off skips the stack, so there is no bottom to go to
+    if (this.fair && this.waiters.length > 0) return this.release(handle);
+
+    const flag = this.slotStateOffset + handle;
+    const prev = Atomics.load(this.state, flag);
+    if (prev === SLOT_AVAILABLE || Atomics.compareExchange(this.state, flag, prev, SLOT_AVAILABLE) !== prev) {
+      this.overReleases++;
+      return false;
+    }
+
+    const others: number[] = [];
+    for (let idx = this.pop(); idx !== -1; idx = this.pop()) others.push(idx);
+    this.push(handle);
+    for (let i = others.length - 1; i >= 0; i--) this.push(others[i]);
+    this.announce(others.length + 1);
+    return true;
+  }
+
+  /**
+   * Release that only succeeds for a slot that is currently checked out.
+   * Throws instead of pushing the slot twice on a double release or an out-of-range handle.
+   */
+  public releaseChecked(handle: number): boolean {
+    this.checkSlot(handle);
+    const flag = this.slotStateOffset + handle;
+    if (Atomics.compareExchange(this.state, flag, SLOT_CHECKED_OUT, SLOT_FREE) !== SLOT_CHECKED_OUT) {
+      throw new PoolError('notCheckedOut', `Slot ${handle} is not checked out`, { slot: handle });
+    }
+    return this.release(handle);
+  }
+
+  /**
+   * Checkout generation of a slot: bumped on every acquire (on any thread), so it identifies the current
+   * checkout. Wraps around after 2^32 acquisitions of the same slot.
+   */
+  public generation(handle: number): number {
+    this.checkSlot(handle);
+    return Atomics.load(this.state, this.generationOffset + handle);
+  }
+
+  /**
+   * release() for the checkout identified by `token` (its generation()). A token from an earlier checkout
+   * of the slot is rejected, so a holder that kept a stale handle can't release someone else's slot.
+   */
+  public releaseWithToken(handle: number, token: number): boolean {
+    this.checkSlot(handle);
+    const error = () =>
+      new PoolError('notCheckedOut', `Slot ${handle} is not checked out under token ${token}`, { slot: handle });
+    const generation = this.generationOffset + handle;
+    // A stale token is turned away without touching the state word, which belongs to the current holder
+    if (Atomics.load(this.state, generation) !== token) throw error();
+    // Claim the slot like releaseChecked(), so a racing release can't push it too
+    const flag = this.slotStateOffset + handle;
+    if (Atomics.compareExchange(this.state, flag, SLOT_CHECKED_OUT, SLOT_FREE) !== SLOT_CHECKED_OUT) throw error();
+    if (Atomics.load(this.state, generation) !== token) {
+      // Released and handed out again between the check and the claim: give the claim back, unless the
+      // new holder has already released it
+      Atomics.compareExchange(this.state, flag, SLOT_FREE, SLOT_CHECKED_OUT);
+      throw error();
+    }
+    return this.release(handle);
+  }
+
+  public isCheckedOut(handle: number): boolean {
+    this.checkSlot(handle);
+    return Atomics.load(this.state, this.slotStateOffset + handle) === SLOT_CHECKED_OUT;
+  }
+
+  /**
+   * Slots on the free stack, in index order. A snapshot: other threads may change it right away.
+   */
+  public availableSlots(): number[] {
+    const slots: number[] = [];
+    for (let i = 0; i < this.capacity; i++) {
+      if (Atomics.load(this.state, this.slotStateOffset + i) === SLOT_AVAILABLE) slots.push(i);
+    }
+    return slots;
+  }
+
+  /**
+   * Is the slot on the free stack right now
+   */
+  public isAvailable(handle: number): boolean {
+    this.checkSlot(handle);
+    return Atomics.load(this.state, this.slotStateOffset + handle) === SLOT_AVAILABLE;
+  }
+
+  /**
+   * Async acquire with timeout support.
+   * Waiters on this thread are queued and served in FIFO order.
+   * A timeout of 0 waits indefinitely; use acquire() for an immediate, timer-free try.
+   * Aborting `signal` leaves the queue and rejects with the signal's reason.
+   */
+  public acquireAsync(timeoutMs?: number, signal?: AbortSignal): Promise<number> {
+    if (this.closed) return Promise.reject(new PoolError('closed', 'Pool is destroyed'));
+    if (signal?.aborted) return Promise.reject(signal.reason);
+
+    // Fast path, unless that would let us jump the queue
+    if (!this.fair || this.waiters.length === 0) {
+      const handle = this.acquire();
+      if (handle !== -1) return Promise.resolve(handle);
+    }
+    if (this.admit && !this.admit()) {
+      return Promise.reject(new PoolError('queueFull', 'Acquire rejected by the admission controller'));
+    }
+
+    return new Promise<number>((resolve, reject) => {
+      const waiter: Waiter = { resolve, reject, timer: null, detach: null, queuedAt: performance.now() };
+
+      if (timeoutMs) {
+        waiter.timer = setTimeout(() => {
+          waiter.detach?.();
+          this.removeWaiter(waiter);
+          reject(new PoolError('timeout', `Timeout acquiring resource (${timeoutMs}ms)`, { timeoutMs }));
+        }, timeoutMs);
+      }
+
+      if (signal) {
+        const onAbort = () => {
+          if (waiter.timer) clearTimeout(waiter.timer);
+          this.removeWaiter(waiter);
+          reject(signal.reason);
+        };
+        signal.addEventListener('abort', onAbort, { once: true });
+        waiter.detach = () => signal.removeEventListener('abort', onAbort);
+      }
+
+      this.waiters.push(waiter);
+      if (this.waiters.length === 1) {
+        // Keep process alive while anyone is waiting
+        this.keepAlive = setInterval(() => {}, 60 * 60 * 1000);
+        if (!this.refed) {
+          this.keepAlive.unref();
+          process.on('beforeExit', this.onBeforeExit);
         }
-        // Yield to avoid burning CPU too hard in high contention
-        // In strict JS environment we can't yield, but we can rely on randomness of OS scheduling
+      }
+      this.watch();
+    });
+  }
+
+  /**
+   * Push a slot whose state the caller already set to AVAILABLE, before it becomes visible,
+   * so the store can't wipe a new owner's flag
+   */
+  private push(handle: number) {
+    const state = this.state;
+    const bits = this.indexBits;
+    const mask = this.indexMask;
+
+    while (true) {
+      const head = Atomics.load(state, OFFSET_HEAD);
+      Atomics.store(state, this.nextOffset + handle, head & mask);
+      const newHead = (((head >>> bits) + 1) << bits) | handle;
+      if (Atomics.compareExchange(state, OFFSET_HEAD, head, newHead) === head) {
+        const count = Atomics.add(state, OFFSET_COUNT, 1) + 1;
+        if (this.checkInvariants && count > this.capacity) {
+          const message = `Pool invariant violated: ${count} slots available but capacity is ${this.capacity}`;
+          throw new PoolError('invariant', message);
+        }
+        return;
       }
     }
+  }
 
-    // 2. Critical Section
-    try {
+  /**
+   * Replace the whole stack with one whose top is `top`, bumping the tag
+   */
+  private swapHead(top: number) {
+    const bits = this.indexBits;
+    while (true) {
+      const head = Atomics.load(this.state, OFFSET_HEAD);
+      const newHead = (((head >>> bits) + 1) << bits) | top;
+      if (Atomics.compareExchange(this.state, OFFSET_HEAD, head, newHead) === head) return;
+    }
+  }
+
+  /**
+   * Walk the free stack and check it is well formed: every link is in range, there are no cycles,
+   * and the number of reachable slots matches COUNT. Diagnostic for buffers corrupted by buggy writers.
+   * Retries while other threads are mutating the stack; exact on a quiescent pool.
+   * @returns false if the stack kept changing under every walk, so nothing could be checked
+   * @throws PoolError (`corrupted`) with the first problem found
+   */
+  public verify(): boolean {
+    const state = this.state;
+    const mask = this.indexMask;
+
+    for (let attempt = 0; attempt < 10; attempt++) {
+      const head = Atomics.load(state, OFFSET_HEAD);
       const count = Atomics.load(state, OFFSET_COUNT);
-      if (count > 0) {
-        const newCount = count - 1;
-        Atomics.store(state, OFFSET_COUNT, newCount);
-        // Pop from stack: read value at newCount index
-        return Atomics.load(state, this.stackOffset + newCount);
+      const seen = new Uint8Array(this.capacity);
+
+      let reachable = 0;
+      let error: string | null = null;
+      for (let idx = head & mask; idx !== mask; idx = Atomics.load(state, this.nextOffset + idx)) {
+        if (idx < 0 || idx >= this.capacity) {
+          error = `slot index ${idx} out of range (capacity ${this.capacity})`;
+          break;
+        }
+        if (seen[idx]) {
+          error = `cycle detected at slot ${idx}`;
+          break;
+        }
+        seen[idx] = 1;
+        reachable++;
       }
-      return -1;
-    } finally {
-      // 3. Unlock
-      Atomics.store(state, OFFSET_LOCK, LOCK_UNLOCKED);
+
+      // Only trust the walk if nothing moved underneath it
+      if (Atomics.load(state, OFFSET_HEAD) !== head || Atomics.load(state, OFFSET_COUNT) !== count) continue;
+
+      if (error) throw new PoolError('corrupted', `Pool buffer corrupted: ${error}`);
+      if (reachable !== count) {
+        throw new PoolError('corrupted', `Pool buffer corrupted: ${reachable} slots reachable but count is ${count}`);
+      }
+      return true;
     }
+
+    // Contention, not corruption: let the caller retry later
+    return false;
   }
 
   /**
-   * O(1) Release
+   * Bit-per-slot bitmap (slot i is bit i % 8 of byte i >> 3) of the slots not on the free stack,
+   * built by inverting a consistent snapshot of the stack.
+   * @returns null if other threads kept the stack changing, so no consistent snapshot could be taken
    */
-  public release(handle: number) {
-    const state = this.state;
+  public inUseBitmap(): Uint8Array | null {
+    const { slots, settled } = this.stackSnapshot();
+    if (!settled) return null;
+    const bitmap = new Uint8Array(Math.ceil(this.capacity / 8));
+    for (let i = 0; i < this.capacity; i++) bitmap[i >> 3] |= 1 << (i & 7);
+    for (const idx of slots) bitmap[idx >> 3] &= ~(1 << (idx & 7));
+    return bitmap;
+  }
 
-    // Spinlock
-    while (Atomics.compareExchange(state, OFFSET_LOCK, LOCK_UNLOCKED, LOCK_LOCKED) !== LOCK_UNLOCKED) {}
+  /**
+   * How many slots are actually linked on the free stack. Matches availableCount() in a healthy pool;
+   * a difference means COUNT drifted from the stack (see verify()).
+   */
+  public stackLength(): number {
+    return this.stackSnapshot().slots.length;
+  }
 
-    try {
+  /**
+   * Slots on the free stack, top first. The walk is retried until HEAD and COUNT held still, so the
+   * result matches a single moment even while other threads acquire and release. If they never do,
+   * the last walk is returned with `settled: false`: a best-effort view that may mix two moments.
+   */
+  private stackSnapshot(): { slots: number[]; settled: boolean } {
+    const state = this.state;
+    const mask = this.indexMask;
+
+    let slots: number[] = [];
+    for (let attempt = 0; attempt < 10; attempt++) {
+      const head = Atomics.load(state, OFFSET_HEAD);
       const count = Atomics.load(state, OFFSET_COUNT);
-      // Push to stack
-      Atomics.store(state, this.stackOffset + count, handle);
-      Atomics.store(state, OFFSET_COUNT, count + 1);
-    } finally {
-      Atomics.store(state, OFFSET_LOCK, LOCK_UNLOCKED);
+      slots = [];
+      // Stops at out-of-range links and after capacity steps so a corrupted chain can't run away
+      for (let idx = head & mask; idx >= 0 && idx < this.capacity && slots.length < this.capacity; ) {
+        slots.push(idx);
+        idx = Atomics.load(state, this.nextOffset + idx);
+      }
+
+      // A walk that raced with a push/pop may have followed stale links, throw it away
+      if (Atomics.load(state, OFFSET_HEAD) === head && Atomics.load(state, OFFSET_COUNT) === count) {
+        return { slots, settled: true };
+      }
     }
 
-    // Notify waiters (outside lock to reduce contention)
-    Atomics.add(state, OFFSET_NOTIFY, 1);
-    Atomics.notify(state, OFFSET_NOTIFY, 1);
+    return { slots, settled: false };
   }
 
   /**
-   * Async acquire with timeout support
+   * Store a u32 alongside a slot, visible to every thread sharing the buffer
    */
-  public async acquireAsync(timeoutMs?: number): Promise<number> {
+  public setPayload(slot: number, value: number) {
+    this.checkSlot(slot);
+    if (!Number.isInteger(value) || value < 0 || value > 0xffffffff) {
+      throw new Error(`Payload must be a u32, got ${value}`);
+    }
+    Atomics.store(this.state, this.payloadOffset + slot, value | 0);
+  }
+
+  public getPayload(slot: number): number {
+    this.checkSlot(slot);
+    return Atomics.load(this.state, this.payloadOffset + slot) >>> 0;
+  }
+
+  /**
+   * Read several payloads at once; every slot is validated before any is read
+   */
+  public getPayloads(slots: number[]): number[] {
+    for (const slot of slots) this.checkSlot(slot);
+    return slots.map((slot) => Atomics.load(this.state, this.payloadOffset + slot) >>> 0);
+  }
+
+  private checkSlot(slot: number) {
+    if (!Number.isInteger(slot) || slot < 0 || slot >= this.capacity) {
+      throw new PoolError('invalidSlot', `Slot ${slot} is out of range (capacity ${this.capacity})`, { slot });
+    }
+  }
+
+  /**
+   * Synchronous acquire that parks the calling thread until a slot frees or the timeout elapses.
+   * Meant for worker threads; on the main thread it blocks the event loop.
+   *
+   * Interop contract: sleepers wait on the NOTIFY word with Atomics.wait, and every push onto the
+   * stack is followed by Atomics.add(NOTIFY, 1) + Atomics.notify(NOTIFY, 1). A release handed off
+   * directly to a local async waiter (fair mode) never reaches the stack and wakes nobody.
+   *
+   * @returns The slot handle, or -1 on timeout (0 / undefined = wait forever)
+   */
+  public acquireBlocking(timeoutMs?: number): number {
+    const state = this.state;
     const deadline = timeoutMs ? Date.now() + timeoutMs : Infinity;
 
     while (true) {
-      // Try sync acquire first
+      // Snapshot before trying so a release in between makes the wait return immediately
+      const notifyValue = Atomics.load(state, OFFSET_NOTIFY);
       const handle = this.acquire();
       if (handle !== -1) return handle;
 
-      if (timeoutMs && Date.now() >= deadline) {
-        throw new Error(`Timeout acquiring resource (${timeoutMs}ms)`);
+      const remaining = deadline - Date.now();
+      if (remaining <= 0) return -1;
+      Atomics.wait(state, OFFSET_NOTIFY, notifyValue, remaining);
+    }
+  }
+
+  /**
+   * Resolve once `isIdle()` holds, re-checking whenever a slot is pushed (on any thread) or the
+   * local waiter queue drains. The caller decides what idle means (ObjectPool also has empty slots).
+   * @throws PoolError (`timeout`) if the pool is still busy after `timeoutMs` (0 = wait forever)
+   */
+  public waitUntilIdle(isIdle: () => boolean, timeoutMs = 0): Promise<void> {
+    const message = `Timeout waiting for the pool to become idle (${timeoutMs}ms)`;
+    return this.pollUntil(() => (isIdle() ? undefined : null), timeoutMs, message);
+  }
+
+  /**
+   * Pop one of `candidates` off the stack, wherever it sits. Only the slots above the first candidate are
+   * lifted, and they go back in reverse so the stack keeps its order.
+   * O(slots above the candidate): other threads may briefly see those missing. They stay AVAILABLE
+   * throughout and nobody is notified until they are back.
+   * @returns The slot handle, or -1 if none of the candidates is free
+   */
+  public acquireFrom(candidates: number[]): number {
+    if (!candidates.some((slot) => this.isAvailable(slot))) return -1;
+
+    const wanted = new Set(candidates);
+    const others: number[] = [];
+    let handle = this.pop();
+    while (handle !== -1 && !wanted.has(handle)) {
+      others.push(handle);
+      handle = this.pop();
+    }
+    if (handle !== -1) this.checkOut(handle);
+    for (let i = others.length - 1; i >= 0; i--) this.push(others[i]);
+
+    if (others.length > 0) this.announce(others.length);
+    else if (handle !== -1 && this.availableWatchers.length > 0) this.emitAvailable();
+    return handle;
+  }
+
+  /**
+   * Async acquireFrom(): waits until one of `candidates` is pushed back (on any thread).
+   * Targeted waiters aren't queued, so they don't take part in FIFO hand-off and fair releases skip them.
+   */
+  public async acquireFromAsync(candidates: number[], timeoutMs = 0): Promise<number> {
+    for (const slot of candidates) this.checkSlot(slot);
+    if (candidates.length === 0) throw new Error('candidates must not be empty');
+
+    const attempt = () => {
+      if (this.closed) throw new PoolError('closed', 'Pool is destroyed');
+      const handle = this.acquireFrom(candidates);
+      return handle === -1 ? null : handle;
+    };
+    return this.pollUntil(attempt, timeoutMs, `Timeout acquiring resource (${timeoutMs}ms)`);
+  }
+
+  /**
+   * Call `attempt` until it returns non-null, re-trying whenever the notify word moves
+   */
+  private async pollUntil<R>(attempt: () => R | null, timeoutMs: number, timeoutMessage: string): Promise<R> {
+    const deadline = timeoutMs > 0 ? Date.now() + timeoutMs : Infinity;
+    // Atomics.waitAsync doesn't hold the event loop open, so a timer enforces the deadline
+    let expire = () => {};
+    const expired = new Promise<void>((resolve) => (expire = resolve));
+    const timer = timeoutMs > 0 ? setTimeout(() => expire(), timeoutMs) : null;
+    if (timer && !this.refed) timer.unref();
+
+    try {
+      while (true) {
+        // Snapshot before checking so a release in between makes the wait return immediately
+        const notifyValue = Atomics.load(this.state, OFFSET_NOTIFY);
+        const result = attempt();
+        if (result !== null) return result;
+
+        const remaining = deadline - Date.now();
+        if (remaining <= 0) throw new PoolError('timeout', timeoutMessage, { timeoutMs });
+        const wait = Atomics.waitAsync(this.state, OFFSET_NOTIFY, notifyValue, remaining);
+        if (wait.async) await Promise.race([wait.value, expired]);
       }
+    } finally {
+      if (timer) clearTimeout(timer);
+    }
+  }
 
-      // Prepare to wait
-      const notifyValue = Atomics.load(this.state, OFFSET_NOTIFY);
+  /**
+   * Call `listener` with the new free count whenever an acquire, release, reset or destroy through this
+   * instance changes it (changes made by other threads are not observed). Returns an unsubscribe function.
+   */
+  public watchAvailable(listener: (available: number) => void): () => void {
+    this.lastAvailable = this.availableCount();
+    this.availableWatchers.push(listener);
+    return () => {
+      this.availableWatchers = this.availableWatchers.filter((watcher) => watcher !== listener);
+    };
+  }
 
-      // Double check before waiting (race condition fix)
-      if (Atomics.load(this.state, OFFSET_COUNT) > 0) continue;
+  private emitAvailable() {
+    const available = this.availableCount();
+    if (available === this.lastAvailable) return;
+    this.lastAvailable = available;
+    for (const watcher of this.availableWatchers) watcher(available);
+  }
 
-      const timeLeft = deadline === Infinity ? Infinity : deadline - Date.now();
-      if (timeLeft <= 0) continue;
+  /**
+   * Async waiters queued on this thread. Timed-out waiters leave the queue before they reject.
+   */
+  public pendingCount() {
+    return this.waiters.length;
+  }
+
+  /**
+   * How many release() calls on this thread were dropped because the slot was not checked out
+   */
+  public overReleaseCount() {
+    return this.overReleases;
+  }
 
-      const result = Atomics.waitAsync(this.state, OFFSET_NOTIFY, notifyValue, timeLeft);
+  public maxWaitMicros() {
+    return this.maxWaitUs;
+  }
 
-      if (result.async) {
-        // Keep process alive during wait
-        const timer = setInterval(() => {}, 60 * 60 * 1000);
-        try {
-          await result.value;
-        } finally {
-          clearInterval(timer);
-        }
+  public resetWaitStats() {
+    this.maxWaitUs = 0;
+  }
+
+  /**
+   * Zero every cumulative counter (wait high-water mark, dropped releases). Live counts are untouched.
+   * The counters belong to this instance and are only updated on its thread, so nothing can race the reset.
+   */
+  public resetStats() {
+    this.maxWaitUs = 0;
+    this.overReleases = 0;
+  }
+
+  public isFair() {
+    return this.fair;
+  }
+
+  private handOff(handle: number) {
+    // The slot changes hands without touching the stack, it stays checked out
+    Atomics.store(this.state, this.slotStateOffset + handle, SLOT_CHECKED_OUT);
+    Atomics.add(this.state, this.generationOffset + handle, 1);
+    const waiter = this.waiters.shift()!;
+    if (this.waiters.length === 0) this.onQueueEmpty();
+    if (waiter.timer) clearTimeout(waiter.timer);
+    waiter.detach?.();
+
+    const waitedUs = Math.round((performance.now() - waiter.queuedAt) * 1000);
+    if (waitedUs > this.maxWaitUs) this.maxWaitUs = waitedUs;
+
+    waiter.resolve(handle);
+  }
+
+  private removeWaiter(waiter: Waiter) {
+    const idx = this.waiters.indexOf(waiter);
+    if (idx === -1) return;
+    this.waiters.splice(idx, 1);
+    if (this.waiters.length === 0) this.onQueueEmpty();
+  }
+
+  private onQueueEmpty() {
+    if (this.keepAlive) {
+      clearInterval(this.keepAlive);
+      this.keepAlive = null;
+    }
+    process.off('beforeExit', this.onBeforeExit);
+    // Wake the watcher so it can exit instead of parking on the notify word forever
+    if (this.watching) Atomics.notify(this.state, OFFSET_NOTIFY);
+  }
+
+  /**
+   * Hand free slots on the stack to queued waiters, oldest first
+   */
+  private serveWaiters() {
+    while (this.waiters.length > 0) {
+      const handle = this.acquire();
+      if (handle === -1) return;
+      this.handOff(handle);
+    }
+  }
+
+  /**
+   * Watch the notify word for releases made by other threads sharing the buffer.
+   * Local releases hand off directly and don't depend on this loop.
+   */
+  private async watch() {
+    if (this.watching) return;
+    this.watching = true;
+
+    try {
+      while (this.waiters.length > 0) {
+        // Snapshot before serving so a release in between can't be missed
+        const notifyValue = Atomics.load(this.state, OFFSET_NOTIFY);
+        this.serveWaiters();
+        if (this.waiters.length === 0) break;
+
+        const result = Atomics.waitAsync(this.state, OFFSET_NOTIFY, notifyValue);
+        if (result.async) await result.value;
       }
+    } finally {
+      this.watching = false;
     }
   }
 
+  /**
+   * Free slots across every thread sharing the buffer.
+   * COUNT is adjusted right after each successful CAS on HEAD, so under contention it may briefly trail the stack.
+   */
   public availableCount() {
-    return Atomics.load(this.state, OFFSET_COUNT);
+    const count = Atomics.load(this.state, OFFSET_COUNT);
+    return count < 0 ? 0 : count > this.capacity ? this.capacity : count;
+  }
+
+  public inUseCount() {
+    return this.capacity - this.availableCount();
   }
 
   public getCapacity() {
     return this.capacity;
   }
 
+  /**
+   * Reclaim every slot by rebuilding the stack as 0..capacity-1.
+   * The new stack is published with a single swap of the tagged head, so concurrent
+   * acquire/release calls never walk a half-built chain.
+   *
+   * Hazard: handles still held by any thread become free as well, and a release racing with
+   * the swap is lost. Releasing a handle acquired before the reset pushes a duplicate onto the
+   * stack, so only reset once all holders are known to be gone (e.g. after worker threads
+   * crashed or a logical epoch ended).
+   */
+  public reset() {
+    const state = this.state;
+    const mask = this.indexMask;
+
+    // Slot capacity-1 ends up on top, matching a pool whose slots were released in order
+    Atomics.store(state, this.nextOffset, mask);
+    for (let i = 1; i < this.capacity; i++) {
+      Atomics.store(state, this.nextOffset + i, i - 1);
+    }
+    for (let i = 0; i < this.capacity; i++) {
+      Atomics.store(state, this.slotStateOffset + i, SLOT_AVAILABLE);
+    }
+    this.swapHead(this.capacity - 1);
+    Atomics.store(state, OFFSET_COUNT, this.capacity);
+
+    Atomics.add(state, OFFSET_NOTIFY, 1);
+    Atomics.notify(state, OFFSET_NOTIFY, Infinity);
+    if (this.availableWatchers.length > 0) this.emitAvailable();
+    this.serveWaiters();
+  }
+
+  /**
+   * Was destroy() called on this instance (other threads attached to the buffer keep their own flag)
+   */
+  public isClosed() {
+    return this.closed;
+  }
+
+  /**
+   * Stop queued async waiters from keeping the process alive. Instead of hanging, if the event loop runs
+   * dry while waiters are queued they are rejected on 'beforeExit', so their handlers get to run. The pool
+   * stays open: held slots can still be released and the owner still decides when to destroy it.
+   */
+  public unref() {
+    if (!this.refed) return;
+    this.refed = false;
+    if (this.keepAlive) {
+      this.keepAlive.unref();
+      process.on('beforeExit', this.onBeforeExit);
+    }
+  }
+
+  /**
+   * Undo unref(): queued waiters keep the process alive again (the default).
+   */
+  public ref() {
+    this.refed = true;
+    this.keepAlive?.ref();
+    process.off('beforeExit', this.onBeforeExit);
+  }
+
+  /**
+   * Fail every async waiter queued on this thread with `reason` (load shedding). Unlike destroy(),
+   * the pool stays open: later acquisitions queue as usual.
+   * @returns How many waiters were rejected
+   */
+  public rejectPending(reason: Error): number {
+    return this.rejectWaiters(reason);
+  }
+
+  /**
+   * Install a check that runs synchronously whenever an acquireAsync() would have to queue (null removes it).
+   * Returning false fails that acquire right away with a `queueFull` PoolError.
+   */
+  public setAdmission(admit: (() => boolean) | null) {
+    this.admit = admit;
+  }
+
+  private rejectWaiters(reason: Error): number {
+    const waiters = this.waiters;
+    this.waiters = [];
+    if (waiters.length > 0) this.onQueueEmpty();
+    for (const waiter of waiters) {
+      if (waiter.timer) clearTimeout(waiter.timer);
+      waiter.detach?.();
+      waiter.reject(reason);
+    }
+    return waiters.length;
+  }
+
   public destroy() {
-    // Lock to prevent operations
-    while (Atomics.compareExchange(this.state, OFFSET_LOCK, LOCK_UNLOCKED, LOCK_LOCKED) !== LOCK_UNLOCKED) {}
+    this.closed = true;
 
+    // Fail every local waiter instead of leaving it to hang until its timeout
+    this.rejectWaiters(new PoolError('closed', 'Pool is destroyed'));
+
+    // Empty the stack so no further acquisitions succeed
+    this.swapHead(this.indexMask);
     Atomics.store(this.state, OFFSET_COUNT, 0);
+    for (let i = 0; i < this.capacity; i++) {
+      Atomics.compareExchange(this.state, this.slotStateOffset + i, SLOT_AVAILABLE, SLOT_FREE);
+    }
     Atomics.store(this.state, OFFSET_NOTIFY, 0);
-    // Release lock just in case, though buffer is technically dead
-    Atomics.store(this.state, OFFSET_LOCK, LOCK_UNLOCKED);
 
     // Wake everyone up so they can fail gracefully
     Atomics.add(this.state, OFFSET_NOTIFY, 1);
     Atomics.notify(this.state, OFFSET_NOTIFY, Infinity);
+    if (this.availableWatchers.length > 0) this.emitAvailable();
+    this.availableWatchers = [];
   }
 }
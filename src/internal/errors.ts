@@ -0,0 +1,38 @@
+/**
+ * What went wrong in a pool operation:
+ * - `timeout`: no resource became free in time (`timeoutMs` is set)
+ * - `closed`: the pool was destroyed or closed at process exit
+ * - `full`: the pool has no free slot to add a resource to
+ * - `queueFull`: an admission controller turned an acquire away instead of queuing it
+ * - `inUse`: the operation needs every resource returned first
+ * - `invalidSlot` / `notCheckedOut`: a slot index was out of range or not held (`slot` is set)
+ * - `invariant` / `corrupted`: the shared buffer is in an inconsistent state
+ */
+export type PoolErrorKind =
+  | 'timeout'
+  | 'closed'
+  | 'full'
+  | 'queueFull'
+  | 'inUse'
+  | 'invalidSlot'
+  | 'notCheckedOut'
+  | 'invariant'
+  | 'corrupted';
+
+/**
+ * Error raised by pool operations. Switch on `kind` rather than matching messages.
+ * Invalid arguments (configuration, capacities, counts) are reported as a plain Error.
+ */
+export class PoolError extends Error {
+  public readonly kind: PoolErrorKind;
+  public readonly timeoutMs?: number;
+  public readonly slot?: number;
+
+  constructor(kind: PoolErrorKind, message: string, details: { timeoutMs?: number; slot?: number } = {}) {
+    super(message);
+    this.name = 'PoolError';
+    this.kind = kind;
+    if (details.timeoutMs !== undefined) this.timeoutMs = details.timeoutMs;
+    if (details.slot !== undefined) this.slot = details.slot;
+  }
+}
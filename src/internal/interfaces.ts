@@ -1,5 +1,25 @@
 export const SLOT_SYMBOL = Symbol('poolSlotIndex');
 
+/**
+ * Record the slot `resource` lives in. Configurable, so untagSlot() can clear it once the resource leaves the pool
+ */
+export function tagSlot(resource: object, idx: number) {
+  Object.defineProperty(resource, SLOT_SYMBOL, {
+    value: idx,
+    writable: false,
+    enumerable: false,
+    configurable: true,
+  });
+}
+
+/**
+ * Forget the slot of a resource that left the pool, so it can be add()ed again (here or to another pool)
+ */
+export function untagSlot(resource: object) {
+  // @ts-ignore
+  delete resource[SLOT_SYMBOL];
+}
+
 export type PoolConfig<T> = {
   min: number;
   max: number;
@@ -7,6 +27,7 @@ export type PoolConfig<T> = {
   resourceFactory: (() => T) | (() => Promise<T>);
   resourceDestroyer?: (resource: T) => void | Promise<void>;
   validateResource?: (resource: T) => boolean | Promise<boolean>;
+  releaseTransform?: (resource: T) => T;
 
   factoryTimeoutMs?: number;
   destroyerTimeoutMs?: number;
@@ -18,7 +39,61 @@ export type PoolConfig<T> = {
 
   idleTimeoutMs?: number;
   scaleDownIntervalMs?: number;
+  resizeCooldownMs?: number;
   acquireTimeoutMs?: number;
+  minAcquireDelayMs?: number;
+
+  fair?: boolean;
+  evictionStrategy?: EvictionStrategy;
+  observer?: PoolObserver<T>;
+};
+
+/**
+ * Which idle resource a shrinking pool destroys first: the most recently released (`newest`, the top of the
+ * free stack), the one released longest ago (`oldest`), or whichever is cheapest to take (`any`).
+ */
+export type EvictionStrategy = 'newest' | 'oldest' | 'any';
+
+/**
+ * Passed to onTimeout: how long the caller waited and how many acquireAsync() calls are still queued
+ */
+export type TimeoutInfo = {
+  waitedMs: number;
+  pending: number;
+};
+
+/**
+ * What an admission controller sees before an acquireAsync() is queued
+ */
+export type AdmissionState = {
+  available: number;
+  pending: number;
+  size: number;
+};
+
+/**
+ * Instrumentation hooks (tracing spans, custom metrics). Called synchronously, keep them cheap.
+ * `R` is the resource for ObjectPool and the slot index for EnginePool.
+ */
+export type PoolObserver<R> = {
+  onAcquire?: (resource: R) => void;
+  // Only for releases that put something back, not dropped double releases
+  onRelease?: (resource: R) => void;
+  onTimeout?: (timeoutMs: number, info: TimeoutInfo) => void;
+};
+
+export type EnginePoolOptions = {
+  /**
+   * Serve async waiters strictly in arrival order (default: true).
+   * When false, released slots go back to the stack and a sync acquire may overtake queued waiters.
+   */
+  fair?: boolean;
+  observer?: PoolObserver<number>;
+  /**
+   * Development aid: throw from release() as soon as the free count exceeds capacity (default: false).
+   * Catches over-release bugs at the call that caused them instead of at a later acquire.
+   */
+  checkInvariants?: boolean;
 };
 
 export type PoolMetrics = {
@@ -29,11 +104,106 @@ export type PoolMetrics = {
   pendingCreates?: number; // Dynamic only
 };
 
+/**
+ * Effective configuration of an ObjectPool with defaults applied; plain JSON, safe to log in bug reports.
+ */
+export type PoolConfigSnapshot = {
+  min: number;
+  max: number;
+  acquireTimeoutMs: number;
+  minAcquireDelayMs: number;
+  factoryTimeoutMs: number;
+  destroyerTimeoutMs: number;
+  validatorTimeoutMs: number;
+  idleTimeoutMs: number;
+  scaleDownIntervalMs: number;
+  resizeCooldownMs: number;
+  fair: boolean;
+  evictionStrategy: EvictionStrategy;
+  bubbleFactoryErrors: boolean;
+  bubbleDestroyerErrors: boolean;
+  bubbleValidationErrors: boolean;
+  hasDestroyer: boolean;
+  hasValidator: boolean;
+  hasReleaseTransform: boolean;
+  hasObserver: boolean;
+};
+
+/**
+ * Outcome of a non-blocking acquire: `empty` when every resource is busy, `closed` once the pool is destroyed.
+ */
+export type TryAcquireResult<T> = { ok: true; resource: T } | { ok: false; reason: 'empty' | 'closed' };
+
+/**
+ * A resource that is currently checked out, as reported by inspectCheckedOut()
+ */
+export type CheckedOutResource<T> = {
+  index: number;
+  heldForMs: number | null;
+  label: string | null;
+  resource: T;
+};
+
+/**
+ * A resource held by reserve(): commit() hands it out, cancel() returns it to the pool. Only one of them may be called.
+ */
+export type Reservation<T> = {
+  commit(): T;
+  cancel(): void;
+};
+
+/**
+ * A resource yielded by guards(); release() returns it to the pool and lets the stream hand out the next one.
+ */
+export type ResourceGuard<T> = {
+  resource: T;
+  release(): void;
+};
+
+/**
+ * What tryRelease() did with a resource
+ */
+export type ReleaseOutcome = 'accepted' | 'rejected' | 'dropped';
+
 export interface IObjectPool<T> {
   acquire(): T | null;
+  tryAcquire(): TryAcquireResult<T>;
   acquireAsync(timeoutMs?: number): Promise<T>;
+  acquireAsyncLabeled(label: string, timeoutMs?: number): Promise<T>;
+  reserve(timeoutMs?: number): Promise<Reservation<T>>;
+  reservedCount(): number;
   release(resource: T): void;
+  tryRelease(resource: T): ReleaseOutcome;
+  recycle(resource: T, reset: (resource: T) => void): ReleaseOutcome;
+  add(resource: T): void;
+  peek(index: number): T | null;
+  removeIndex(index: number): T | null;
+  takeAsync(timeoutMs?: number): Promise<{ resource: T; index: number }>;
+  replaceExpired(maxAgeMs: number): Promise<T[]>;
+  replaceAll(resources: T[], timeoutMs?: number): Promise<T[]>;
   use<R>(fn: (resource: T) => R | Promise<R>, timeoutMs?: number): Promise<R>;
-  destroy(): Promise<void>;
+  useEach<I, R>(inputs: I[], fn: (resource: T, input: I) => R | Promise<R>, timeoutMs?: number): Promise<R[]>;
+  guards(maxConcurrent: number, timeoutMs?: number): AsyncGenerator<ResourceGuard<T>>;
+  destroy(): Promise<number>;
+  destroyWith(finalizer: (resource: T) => void): number;
+  waitUntilIdle(timeoutMs?: number): Promise<void>;
+  rejectPending(reason: Error): number;
+  setAdmissionController(controller: ((state: AdmissionState) => boolean) | null): void;
+  requestSize(target: number): void;
+  pendingResizeTarget(): number | null;
+  onMemoryPressure(): number;
+  unref(): void;
+  ref(): void;
+  intoInner(): T[];
+  inspectCheckedOut(): CheckedOutResource<T>[];
+  drainInto(sink: (resource: T) => void | boolean | Promise<void | boolean>): Promise<number>;
+  watchAvailable(listener: (available: number) => void): () => void;
+  isFair(): boolean;
+  overReleaseCount(): number;
+  skippedSlotCount(): number;
+  maxWaitMicros(): number;
+  resetWaitStats(): void;
+  resetStats(): void;
+  getConfig(): PoolConfigSnapshot;
   getMetrics(): PoolMetrics;
 }
@@ -0,0 +1,36 @@
+import diagnosticsChannel from 'node:diagnostics_channel';
+import { PoolError } from './errors';
+
+/**
+ * Published on the `resource-pool:acquire` diagnostics channel once an async acquisition settles:
+ * - `outcome`: `acquired`, `timeout`, `closed` (pool destroyed) or `error` (anything else, e.g. an abort)
+ * - `waitedMs`: time from the call until it settled
+ */
+export type AcquireTrace = {
+  pool: 'ObjectPool' | 'EnginePool';
+  timeoutMs: number;
+  waitedMs: number;
+  outcome: 'acquired' | 'timeout' | 'closed' | 'error';
+};
+
+export const ACQUIRE_CHANNEL = 'resource-pool:acquire';
+
+const acquireChannel = diagnosticsChannel.channel(ACQUIRE_CHANNEL);
+
+/**
+ * Whether anyone subscribed to the acquire channel; check before building a trace so untraced pools pay nothing
+ */
+export function isAcquireTraced(): boolean {
+  return acquireChannel.hasSubscribers;
+}
+
+export function traceAcquire(pool: AcquireTrace['pool'], timeoutMs: number, start: number, error?: unknown) {
+  let outcome: AcquireTrace['outcome'] = 'acquired';
+  if (error instanceof PoolError && (error.kind === 'timeout' || error.kind === 'closed')) {
+    outcome = error.kind;
+  } else if (error !== undefined) {
+    outcome = 'error';
+  }
+  const trace: AcquireTrace = { pool, timeoutMs, waitedMs: Date.now() - start, outcome };
+  acquireChannel.publish(trace);
+}
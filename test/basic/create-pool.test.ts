@@ -1,6 +1,6 @@
 import { test } from 'node:test';
 import assert from 'node:assert';
-import { createPool } from '../../src/index';
+import { createPool, PoolBuilder, suggestSize } from '../../src/index';
 
 const createTestFactory = (id?: number) => {
   let counter = id ?? 0;
@@ -28,3 +28,105 @@ test('createPool - throws when max < min', async () => {
     },
   );
 });
+
+test("suggestSize - applies Little's Law", () => {
+  assert.equal(suggestSize(100, 50), 5, '100 req/s x 50ms = 5');
+  assert.equal(suggestSize(1000, 20), 20);
+  assert.equal(suggestSize(30, 100), 3);
+  assert.equal(suggestSize(7, 100), 1, 'Fractional demand rounds up');
+  assert.equal(suggestSize(15, 100), 2);
+  assert.equal(suggestSize(0, 50), 1, 'A pool needs at least one resource');
+});
+
+test('suggestSize - rejects invalid inputs', () => {
+  assert.throws(() => suggestSize(-1, 50), /throughput must be a non-negative number/);
+  assert.throws(() => suggestSize(100, NaN), /avgHoldMs must be a non-negative number/);
+  assert.throws(() => suggestSize(Infinity, 10), /throughput must be a non-negative number/);
+});
+
+test('PoolBuilder - applies each configured option', async () => {
+  const acquired: number[] = [];
+  const pool = new PoolBuilder<{ id: number; created: number }>()
+    .size(1, 2)
+    .factory(createTestFactory(10))
+    .acquireTimeout(30)
+    .minAcquireDelay(10)
+    .evictionStrategy('oldest')
+    .releaseTransform((res) => ({ id: res.id + 100, created: res.created }))
+    .fair(false)
+    .observer({ onAcquire: (res) => acquired.push(res.id) })
+    .build();
+
+  assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 2, pendingCreates: 0 });
+  assert.equal(pool.isFair(), false);
+  assert.equal(pool.getConfig().evictionStrategy, 'oldest');
+
+  const first = pool.acquire()!;
+  assert.equal(first.id, 10, 'Resources should come from the configured factory');
+  const start = Date.now();
+  const second = await pool.acquireAsync();
+  assert.ok(Date.now() - start >= 9, 'acquireAsync should take at least the configured delay');
+  assert.deepEqual(acquired, [10, 11], 'Observer should see every acquisition');
+
+  await assert.rejects(pool.acquireAsync(), /Timeout acquiring resource \(30ms\)/);
+
+  pool.release(first);
+  const recycled = pool.acquire()!;
+  assert.equal(recycled.id, 110, 'Releases should go through the transform');
+
+  pool.release(recycled);
+  pool.release(second);
+  await pool.destroy();
+});
+
+test('PoolBuilder - uses initial resources, validation and destroyer', async () => {
+  const destroyed: number[] = [];
+  const pool = new PoolBuilder<{ id: number; healthy: boolean }>()
+    .factory(() => ({ id: 99, healthy: true }))
+    .validate((res) => res.healthy)
+    .destroyer((res) => {
+      destroyed.push(res.id);
+    })
+    .initialResources([{ id: 1, healthy: false }])
+    .build();
+
+  assert.equal(pool.getMetrics().capacity, 1, 'A static pool should be sized by its initial resources');
+
+  const res = await pool.acquireAsync();
+  assert.equal(res.id, 99, 'Invalid initial resource should be replaced by the factory');
+  assert.deepEqual(destroyed, [1], 'Replaced resource should go through the destroyer');
+
+  pool.release(res);
+  await pool.destroy();
+});
+
+test('PoolBuilder - covers the timeout, error and scaling options', async () => {
+  const pool = new PoolBuilder<{ id: number; created: number }>()
+    .size(0, 2)
+    .factory(createTestFactory())
+    .factoryTimeout(1200)
+    .destroyerTimeout(800)
+    .validatorTimeout(600)
+    .bubbleFactoryErrors()
+    .bubbleDestroyerErrors(true)
+    .bubbleValidationErrors(false)
+    .scaleDownInterval(40)
+    .resizeCooldown(20)
+    .build();
+
+  const config = pool.getConfig();
+  assert.equal(config.factoryTimeoutMs, 1200);
+  assert.equal(config.destroyerTimeoutMs, 800);
+  assert.equal(config.validatorTimeoutMs, 600);
+  assert.equal(config.bubbleFactoryErrors, true);
+  assert.equal(config.bubbleDestroyerErrors, true);
+  assert.equal(config.bubbleValidationErrors, false);
+  assert.equal(config.scaleDownIntervalMs, 40);
+  assert.equal(config.resizeCooldownMs, 20);
+  await pool.destroy();
+});
+
+test('PoolBuilder - build validates like createPool', () => {
+  assert.throws(() => new PoolBuilder().size(1, 2).build(), /resourceFactory is required/);
+  assert.throws(() => new PoolBuilder().size(5, 2).factory(createTestFactory()).build(), /max must be >= min/);
+});
@@ -1,6 +1,7 @@
 import { test } from 'node:test';
 import assert from 'node:assert';
 import { createPool } from '../../src/index';
+import { BasePool } from '../../src/internal/base-pool';
 
 interface TestResource {
   id: number;
@@ -256,3 +257,1079 @@ test('ObjectPool - destroy() stops scale down timer', async () => {
   // If we reach here, destroy completed without hanging
   assert(true);
 });
+
+test('ObjectPool - intoInner returns all resources without destroying them', async () => {
+  let destroyed = 0;
+  let counter = 0;
+
+  const pool = createPool({
+    min: 3,
+    max: 3,
+    resourceFactory: () => ({ id: counter++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+
+  const items = pool.intoInner();
+  assert.equal(items.length, 3, 'Should return every pooled resource');
+  assert.deepEqual(items.map((r) => r.id).sort(), [0, 1, 2]);
+  assert.equal(destroyed, 0, 'Resources should not be destroyed');
+  assert.equal(pool.acquire(), null, 'Pool should be closed afterwards');
+});
+
+test('ObjectPool - intoInner throws while resources are checked out', async () => {
+  let counter = 0;
+
+  const pool = createPool({
+    min: 2,
+    max: 2,
+    resourceFactory: () => ({ id: counter++, created: Date.now() }),
+  });
+
+  const res = pool.acquire();
+  assert(res !== null);
+
+  assert.throws(() => pool.intoInner(), (err: Error) => err.message.includes('in use'));
+
+  pool.release(res);
+  assert.equal(pool.intoInner().length, 2, 'Should reclaim once everything is returned');
+});
+
+test('ObjectPool - empty pool is filled via add', async () => {
+  const pool = createPool({
+    min: 0,
+    max: 2,
+    resourceFactory: () => ({ id: -1, created: Date.now() }),
+  });
+
+  let metrics = pool.getMetrics();
+  assert.equal(metrics.size, 0, 'Empty pool should have no resources');
+  assert.equal(metrics.available, 0, 'Empty pool should have nothing available');
+  assert.equal(pool.acquire(), null, 'Sync acquire should not create resources');
+
+  const added = { id: 1, created: Date.now() };
+  pool.add(added);
+
+  metrics = pool.getMetrics();
+  assert.equal(metrics.size, 1, 'Added resource should count towards size');
+  assert.equal(metrics.available, 1, 'Added resource should be available');
+
+  const res = pool.acquire();
+  assert.equal(res, added, 'Should acquire the added resource');
+  pool.release(res!);
+
+  pool.add({ id: 2, created: Date.now() });
+  assert.throws(() => pool.add({ id: 3, created: Date.now() }), (err: Error) => err.message.includes('capacity'));
+
+  await pool.destroy();
+});
+
+test('ObjectPool - fast-path slots resolve to the object stored in that slot', async () => {
+  const initial = [
+    { id: 0, created: 0 },
+    { id: 1, created: 0 },
+    { id: 2, created: 0 },
+  ];
+  const pool = createPool<TestResource>({ resourceFactory: () => ({ id: -1, created: 0 }) }, initial);
+
+  const acquired = [pool.acquire()!, pool.acquire()!, pool.acquire()!];
+  assert.equal(pool.acquire(), null, 'Pool should be exhausted');
+  assert.equal(new Set(acquired).size, 3, 'Each slot should map to a distinct object');
+  assert.ok(acquired.every((r) => initial.includes(r)), 'Acquired objects should be the ones placed in the pool');
+
+  // The released slot is back on top of the stack and resolves to the same object
+  pool.release(initial[1]);
+  assert.strictEqual(pool.acquire(), initial[1]);
+
+  for (const r of initial) pool.release(r);
+  await pool.destroy();
+});
+
+test('ObjectPool - destroy with checked-out resources resets size and ignores late releases', async () => {
+  const pool = createPool<TestResource>({ min: 2, max: 2, resourceFactory: () => ({ id: 0, created: Date.now() }) });
+
+  const held = pool.acquire()!;
+  await pool.destroy();
+
+  assert.equal(pool.getMetrics().size, 0, 'Size should be 0 after destroy');
+  assert.equal(pool.getMetrics().busy, 0);
+
+  assert.doesNotThrow(() => pool.release(held), 'Releasing into a destroyed pool should be a no-op');
+  assert.equal(pool.acquire(), null, 'Late release must not make the resource acquirable again');
+  assert.equal(pool.getMetrics().available, 0);
+  assert.equal(pool.getMetrics().size, 0);
+});
+
+test('ObjectPool - tryAcquire reports why acquisition failed', async () => {
+  const pool = createPool<TestResource>({ min: 1, max: 1, resourceFactory: () => ({ id: 0, created: Date.now() }) });
+
+  const first = pool.tryAcquire();
+  assert.ok(first.ok, 'Free resource should be acquired');
+
+  assert.deepEqual(pool.tryAcquire(), { ok: false, reason: 'empty' }, 'Exhausted pool should report empty');
+
+  pool.release(first.resource);
+  await pool.destroy();
+  assert.deepEqual(pool.tryAcquire(), { ok: false, reason: 'closed' }, 'Destroyed pool should report closed');
+});
+
+test('ObjectPool - unref/ref toggle whether background timers keep the process alive', async () => {
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 3,
+    resourceFactory: () => ({ id: 0, created: Date.now() }),
+    scaleDownIntervalMs: 60000,
+  });
+  const internals = pool as unknown as { scaleDownTimer: NodeJS.Timeout };
+
+  assert.equal(internals.scaleDownTimer.hasRef(), true, 'Timers are referenced by default');
+
+  pool.unref();
+  assert.equal(internals.scaleDownTimer.hasRef(), false, 'unref should release the event loop');
+  const res = pool.acquire();
+  assert.ok(res, 'Resources stay retrievable while unref-ed');
+  pool.release(res);
+
+  pool.ref();
+  assert.equal(internals.scaleDownTimer.hasRef(), true, 'ref should hold the event loop again');
+
+  await pool.destroy();
+});
+
+test('ObjectPool - drainInto streams every resource to the consumer', async () => {
+  let counter = 0;
+  const pool = createPool<TestResource>({ min: 4, max: 4, resourceFactory: () => ({ id: counter++, created: 0 }) });
+
+  const received: number[] = [];
+  const delivered = await pool.drainInto(async (res) => {
+    await new Promise((resolve) => setImmediate(resolve));
+    received.push(res.id);
+  });
+
+  assert.equal(delivered, 4);
+  assert.deepEqual(received.sort(), [0, 1, 2, 3], 'Consumer should receive each resource once');
+  assert.throws(() => pool.intoInner(), /Pool is destroyed/, 'Drained pool should be shut down');
+});
+
+test('ObjectPool - drainInto destroys what a closed consumer did not take', async () => {
+  let counter = 0;
+  const destroyed: number[] = [];
+  const pool = createPool<TestResource>({
+    min: 4,
+    max: 4,
+    resourceFactory: () => ({ id: counter++, created: 0 }),
+    resourceDestroyer: (res) => {
+      destroyed.push(res.id);
+    },
+  });
+
+  const received: number[] = [];
+  const delivered = await pool.drainInto((res) => {
+    if (received.length === 2) return false; // Receiver closed
+    received.push(res.id);
+  });
+
+  assert.equal(delivered, 2, 'Only resources accepted before closing count as delivered');
+  assert.equal(destroyed.length, 2, 'Undelivered resources should be destroyed');
+  assert.deepEqual([...received, ...destroyed].sort(), [0, 1, 2, 3]);
+});
+
+test('ObjectPool - drainInto destroys the rest when the consumer throws', async () => {
+  let counter = 0;
+  const destroyed: number[] = [];
+  const pool = createPool<TestResource>({
+    min: 3,
+    max: 3,
+    resourceFactory: () => ({ id: counter++, created: 0 }),
+    resourceDestroyer: (res) => {
+      destroyed.push(res.id);
+    },
+  });
+
+  const received: number[] = [];
+  await assert.rejects(
+    pool.drainInto(async (res) => {
+      if (received.length === 1) throw new Error('receiver failed');
+      received.push(res.id);
+    }),
+    /receiver failed/,
+  );
+  assert.equal(destroyed.length, 2, 'The resource it threw on and the undelivered one are destroyed');
+  assert.deepEqual([...received, ...destroyed].sort(), [0, 1, 2]);
+});
+
+test('ObjectPool - requestSize coalesces rapid resizes into the final target', async () => {
+  let created = 0;
+  let destroyed = 0;
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 5,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+    resizeCooldownMs: 50,
+  });
+
+  pool.requestSize(5);
+  pool.requestSize(2);
+  pool.requestSize(3);
+  assert.equal(pool.pendingResizeTarget(), 3, 'Latest target should be pending');
+  assert.equal(pool.getMetrics().size, 1, 'Nothing should change before the cooldown');
+
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  assert.equal(pool.pendingResizeTarget(), null);
+  assert.equal(pool.getMetrics().size, 3, 'Only the final target should be applied');
+  assert.equal(created, 3, 'Intermediate targets should not create resources');
+  assert.equal(destroyed, 0);
+
+  pool.requestSize(1);
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  assert.equal(pool.getMetrics().size, 1, 'Shrinking should destroy idle resources');
+  assert.equal(destroyed, 2);
+
+  assert.throws(() => pool.requestSize(6), /target must be between min \(1\) and max \(5\)/);
+  await pool.destroy();
+});
+
+test('ObjectPool - a throwing destroyer does not leak slots when the pool shrinks', async () => {
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 3,
+    resourceFactory: () => ({ id: 0, created: Date.now() }),
+    resourceDestroyer: () => {
+      throw new Error('close failed');
+    },
+    bubbleDestroyerErrors: true,
+    resizeCooldownMs: 10,
+  });
+  const logged: unknown[] = [];
+  const error = console.error;
+  console.error = (...args: unknown[]) => logged.push(args);
+  try {
+    pool.requestSize(3);
+    await new Promise((resolve) => setTimeout(resolve, 30));
+    pool.requestSize(1);
+    await new Promise((resolve) => setTimeout(resolve, 30));
+    assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 3, pendingCreates: 0 });
+
+    pool.requestSize(3);
+    await new Promise((resolve) => setTimeout(resolve, 30));
+    assert.equal(pool.onMemoryPressure(), 2);
+    await new Promise((resolve) => setTimeout(resolve, 10));
+    assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 3, pendingCreates: 0 });
+    assert.equal(logged.length, 4, 'Each failed destroy is logged, not left unhandled');
+  } finally {
+    console.error = error;
+  }
+  await pool.destroy().catch(() => {});
+});
+
+test('ObjectPool - getConfig reports the effective configuration', async () => {
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 4,
+    resourceFactory: () => ({ id: 0, created: Date.now() }),
+    validateResource: () => true,
+    acquireTimeoutMs: 250,
+    fair: false,
+    factoryTimeoutMs: 1200,
+    bubbleFactoryErrors: true,
+  });
+
+  const config = pool.getConfig();
+  assert.deepEqual(config, {
+    min: 1,
+    max: 4,
+    acquireTimeoutMs: 250,
+    minAcquireDelayMs: 0,
+    factoryTimeoutMs: 1200,
+    destroyerTimeoutMs: 5000,
+    validatorTimeoutMs: 3000,
+    idleTimeoutMs: 30000,
+    scaleDownIntervalMs: 10000,
+    resizeCooldownMs: 1000,
+    fair: false,
+    evictionStrategy: 'newest',
+    bubbleFactoryErrors: true,
+    bubbleDestroyerErrors: false,
+    bubbleValidationErrors: false,
+    hasDestroyer: false,
+    hasValidator: true,
+    hasReleaseTransform: false,
+    hasObserver: false,
+  });
+  assert.deepEqual(JSON.parse(JSON.stringify(config)), config, 'Snapshot should survive JSON serialization');
+
+  await pool.destroy();
+});
+
+test('ObjectPool - release returns the exact slot the resource came from', async () => {
+  let created = 0;
+  const pool = createPool<TestResource>({
+    min: 1000,
+    max: 1000,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+
+  const held: TestResource[] = [];
+  for (let i = 0; i < 1000; i++) held.push(pool.acquire()!);
+  assert.equal(pool.acquire(), null);
+
+  // The slot index travels with the resource, so release does not search for it
+  const middle = held[500];
+  pool.release(middle);
+  assert.equal(pool.acquire(), middle, 'The freed slot should hold the released resource');
+
+  for (const res of held) pool.release(res);
+  assert.equal(pool.getMetrics().available, 1000);
+  await pool.destroy();
+});
+
+test('ObjectPool - releasing a resource twice does not inflate the pool', async () => {
+  let created = 0;
+  const pool = createPool<TestResource>({
+    min: 2,
+    max: 2,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+
+  const res = pool.acquire()!;
+  pool.release(res);
+  pool.release(res);
+  assert.equal(pool.overReleaseCount(), 1);
+  assert.deepEqual(pool.getMetrics(), { size: 2, available: 2, busy: 0, capacity: 2, pendingCreates: 0 });
+
+  const first = pool.acquire()!;
+  const second = pool.acquire()!;
+  assert.notEqual(first, second, 'Each resource should have a single owner');
+  assert.equal(pool.acquire(), null);
+
+  pool.release(first);
+  pool.release(second);
+  await pool.destroy();
+});
+
+test('ObjectPool - minAcquireDelayMs sets a floor on acquireAsync', async () => {
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ id: 0, created: Date.now() }),
+    minAcquireDelayMs: 100,
+  });
+
+  const start = Date.now();
+  const res = await pool.acquireAsync();
+  const elapsed = Date.now() - start;
+  assert.ok(elapsed >= 95, `Immediately available acquire should still take ~100ms (took ${elapsed}ms)`);
+  assert.equal(pool.acquire(), null, 'The resource is checked out while the floor elapses');
+
+  pool.release(res);
+  assert.equal(pool.acquire(), res, 'Sync acquire is not delayed');
+  pool.release(res);
+  await pool.destroy();
+});
+
+test('ObjectPool - inspectCheckedOut reports holders with labels and hold times', async () => {
+  let created = 0;
+  const pool = createPool<TestResource>({
+    min: 3,
+    max: 3,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+
+  const early = await pool.acquireAsyncLabeled('job-1');
+  await new Promise((resolve) => setTimeout(resolve, 60));
+  const late = await pool.acquireAsyncLabeled('job-2');
+  const anonymous = pool.acquire()!;
+
+  const report = pool.inspectCheckedOut();
+  assert.equal(report.length, 3);
+  const byLabel = new Map(report.map((entry) => [entry.label, entry]));
+
+  assert.equal(byLabel.get('job-1')!.resource, early);
+  assert.ok(byLabel.get('job-1')!.heldForMs! >= 50, 'Earlier holder should report a longer hold time');
+  assert.ok(byLabel.get('job-2')!.heldForMs! < byLabel.get('job-1')!.heldForMs!);
+  assert.equal(byLabel.get('job-2')!.resource, late);
+  assert.equal(byLabel.get(null)!.resource, anonymous, 'Unlabeled acquisitions are reported too');
+  assert.equal(byLabel.get(null)!.heldForMs, null);
+
+  pool.release(early);
+  const labels = pool.inspectCheckedOut().map((entry) => entry.label);
+  assert.deepEqual(labels.sort(), ['job-2', null].sort(), 'Released resources should drop out of the report');
+
+  pool.release(late);
+  pool.release(anonymous);
+  assert.deepEqual(pool.inspectCheckedOut(), []);
+  await pool.destroy();
+});
+
+test('ObjectPool - tryRelease reports accepted, rejected and dropped', async () => {
+  let created = 0;
+  const factory = () => ({ id: created++, created: Date.now() });
+  const pool = createPool<TestResource>({ min: 1, max: 1, resourceFactory: factory });
+  const other = createPool<TestResource>({ min: 1, max: 1, resourceFactory: factory });
+
+  const res = pool.acquire()!;
+  assert.equal(pool.tryRelease(res), 'accepted');
+  assert.equal(pool.tryRelease(res), 'dropped', 'A second release should be dropped');
+
+  const foreign = other.acquire()!;
+  assert.equal(pool.tryRelease(foreign), 'rejected', 'A resource from another pool is rejected even for the same slot');
+  assert.equal(pool.tryRelease({ id: -1, created: 0 }), 'rejected', 'Objects never added to the pool are rejected');
+  assert.equal(other.tryRelease(foreign), 'accepted');
+  assert.equal(pool.getMetrics().available, 1, 'Rejected releases should not touch the pool');
+
+  const held = pool.acquire()!;
+  await pool.destroy();
+  assert.equal(pool.tryRelease(held), 'dropped', 'Releases after destroy are dropped');
+  await other.destroy();
+});
+
+test('ObjectPool - removeIndex takes a specific idle resource out of the pool', async () => {
+  let created = 0;
+  let destroyed = 0;
+  const pool = createPool<TestResource>({
+    min: 3,
+    max: 3,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+
+  const held = pool.acquire()!;
+  const [{ index: heldIndex }] = pool.inspectCheckedOut();
+  assert.equal(pool.removeIndex(heldIndex), null, 'A checked-out resource cannot be removed');
+
+  // Slot 0 is at the bottom of the free stack
+  const idleIndex = heldIndex === 0 ? 1 : 0;
+  const removed = pool.removeIndex(idleIndex);
+  assert.ok(removed, 'The idle resource should be returned');
+  assert.equal(destroyed, 0, 'Removed resources are handed back, not destroyed');
+  assert.equal(pool.removeIndex(idleIndex), null, 'The slot is empty now');
+  assert.equal(pool.removeIndex(99), null);
+  assert.deepEqual(pool.getMetrics(), { size: 2, available: 1, busy: 1, capacity: 3, pendingCreates: 0 });
+
+  const remaining = pool.acquire()!;
+  assert.notEqual(remaining, removed, 'The removed resource should never be handed out');
+  assert.equal(pool.acquire(), null);
+
+  pool.release(held);
+  pool.release(remaining);
+  await pool.destroy();
+  assert.equal(destroyed, 2, 'Only resources still in the pool are destroyed');
+});
+
+test('ObjectPool - resources that leave a pool can be added again', async () => {
+  const pool = createPool<TestResource>({ min: 2, max: 2, resourceFactory: () => ({ id: 0, created: 0 }) });
+  const inPool = pool.acquire()!;
+  assert.throws(() => pool.add(inPool), /not in a pool/, 'A pooled resource cannot be added twice');
+  pool.release(inPool);
+
+  const removed = pool.removeIndex(0)!;
+  assert.equal(pool.tryRelease(removed), 'rejected', 'The removed resource no longer belongs to the pool');
+  pool.add(removed);
+  assert.equal(pool.getMetrics().size, 2);
+
+  const { resource: taken } = await pool.takeAsync();
+  pool.add(taken);
+  assert.equal(pool.getMetrics().available, 2);
+
+  const other = createPool<TestResource>({ min: 0, max: 2, resourceFactory: () => ({ id: 1, created: 0 }) });
+  for (const res of pool.intoInner()) other.add(res);
+  assert.equal(other.getMetrics().available, 2, 'Reclaimed resources move to another pool');
+  await other.destroy();
+});
+
+test('ObjectPool - watchAvailable follows acquire and release', async () => {
+  const pool = createPool<TestResource>({ min: 2, max: 2, resourceFactory: () => ({ id: 0, created: 0 }) });
+  const seen: number[] = [];
+  pool.watchAvailable((available) => seen.push(available));
+
+  const res = await pool.acquireAsync();
+  pool.release(res);
+  await pool.use(() => {});
+  assert.deepEqual(seen, [1, 2, 1, 2]);
+
+  await pool.destroy();
+  assert.deepEqual(seen, [1, 2, 1, 2, 0], 'Destroy empties the pool');
+});
+
+test('ObjectPool - reservations commit or roll back across pools', async () => {
+  const factory = () => ({ id: 0, created: Date.now() });
+  const connections = createPool<TestResource>({ min: 1, max: 1, resourceFactory: factory });
+  const buffers = createPool<TestResource>({ min: 1, max: 1, resourceFactory: factory });
+
+  // Both reservations succeed: commit them together
+  const [conn, buf] = await Promise.all([connections.reserve(100), buffers.reserve(100)]);
+  assert.equal(connections.getMetrics().available, 0, 'A reservation holds its resource');
+  assert.equal(connections.reservedCount(), 1);
+  const resources = [conn.commit(), buf.commit()];
+  assert.equal(connections.reservedCount(), 0, 'A committed reservation is an ordinary checkout');
+  assert.equal(connections.getMetrics().busy, 1);
+  assert.throws(() => conn.cancel(), /Reservation already committed or cancelled/);
+  connections.release(resources[0]);
+  buffers.release(resources[1]);
+
+  // The second reservation fails: roll back the first
+  const blocker = buffers.acquire()!;
+  const first = await connections.reserve(100);
+  assert.equal(connections.getMetrics().available, 0, 'Reserving takes the resource immediately');
+  await assert.rejects(buffers.reserve(30), /Timeout/);
+  assert.equal(buffers.reservedCount(), 0, 'A failed reservation is not counted');
+  first.cancel();
+  assert.equal(connections.reservedCount(), 0);
+  assert.deepEqual(connections.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 1, pendingCreates: 0 });
+  assert.throws(() => first.commit(), /Reservation already committed or cancelled/);
+
+  buffers.release(blocker);
+  await connections.destroy();
+  await buffers.destroy();
+});
+
+test('ObjectPool - recycle resets a resource and makes it available again', async () => {
+  let destroyed = 0;
+  const pool = createPool<{ items: number[] }>({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ items: [] }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+
+  const buf = pool.acquire()!;
+  buf.items.push(1, 2, 3);
+  assert.equal(pool.recycle(buf, (b) => (b.items.length = 0)), 'accepted');
+  assert.equal(pool.acquire(), buf, 'The recycled object should be available again');
+  assert.deepEqual(buf.items, [], 'Its state should be reset');
+  pool.release(buf);
+
+  // An idle resource is turned away before reset runs, so a failing reset cannot retire a slot in rotation
+  let resets = 0;
+  const idleReset = () => {
+    resets++;
+    throw new Error('reset failed');
+  };
+  assert.throws(() => pool.recycle(buf, idleReset), { name: 'PoolError', kind: 'notCheckedOut' });
+  assert.equal(resets, 0);
+  assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 1, pendingCreates: 0 });
+  assert.equal(pool.acquire(), buf);
+
+  assert.equal(pool.recycle({ items: [] }, () => {}), 'rejected', 'Foreign objects are not accepted');
+
+  // A failing reset takes the object out of the pool
+  const failingReset = () => {
+    throw new Error('reset failed');
+  };
+  assert.throws(() => pool.recycle(buf, failingReset), /reset failed/);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.equal(destroyed, 1, 'The half-reset object should be destroyed');
+  assert.equal(pool.getMetrics().size, 0);
+
+  const fresh = await pool.acquireAsync(1000);
+  assert.notEqual(fresh, buf, 'A new resource should be created in its place');
+  pool.release(fresh);
+  await pool.destroy();
+});
+
+test('ObjectPool - destroy resolves with the number of resources let go', async () => {
+  const pool = createPool<TestResource>({ min: 0, max: 5, resourceFactory: () => ({ id: -1, created: 0 }) });
+  for (let i = 0; i < 4; i++) pool.add({ id: i, created: Date.now() });
+  const held = pool.acquire()!;
+
+  assert.equal(await pool.destroy(), 4, 'Checked-out resources count too, empty slots do not');
+  assert.equal(await pool.destroy(), 0, 'A second destroy has nothing left to release');
+  pool.release(held);
+});
+
+test('ObjectPool - takeAsync moves a resource out of the pool', async () => {
+  let created = 0;
+  let destroyed = 0;
+  const pool = createPool<TestResource>({
+    min: 2,
+    max: 2,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+
+  const { resource, index } = await pool.takeAsync();
+  assert.equal(resource.id, index);
+  assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 2, pendingCreates: 0 });
+  assert.equal(pool.tryRelease(resource), 'rejected', 'A taken resource no longer belongs to the pool');
+
+  const first = pool.acquire()!;
+  const refilled = await pool.acquireAsync(100);
+  assert.notEqual(first, resource);
+  assert.notEqual(refilled, resource, 'The slot is refilled with a new resource');
+  assert.equal(created, 3);
+
+  pool.release(first);
+  pool.release(refilled);
+  assert.equal(await pool.destroy(), 2);
+  assert.equal(destroyed, 2, 'The taken resource is not destroyed with the pool');
+});
+
+test('ObjectPool - rejectPending sheds queued acquisitions', async () => {
+  const pool = createPool<TestResource>({ min: 1, max: 1, resourceFactory: () => ({ id: 1, created: Date.now() }) });
+  const held = pool.acquire()!;
+
+  const waiters = [pool.acquireAsync(1000), pool.acquireAsync(1000)];
+  assert.equal(pool.rejectPending(new Error('overloaded')), 2);
+  for (const waiter of waiters) await assert.rejects(waiter, /overloaded/);
+
+  const next = pool.acquireAsync(1000);
+  pool.release(held);
+  assert.equal(await next, held);
+  pool.release(held);
+  await pool.destroy();
+});
+
+test('ObjectPool - guards caps how many resources are out at once', async () => {
+  let created = 0;
+  const pool = createPool<TestResource>({
+    min: 5,
+    max: 5,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+
+  let alive = 0;
+  let peak = 0;
+  let yielded = 0;
+  const work: Promise<void>[] = [];
+  for await (const guard of pool.guards(2)) {
+    alive++;
+    peak = Math.max(peak, alive);
+    work.push(
+      new Promise<void>((resolve) => setTimeout(resolve, 5)).then(() => {
+        alive--;
+        guard.release();
+        guard.release(); // releasing a guard twice is harmless
+      }),
+    );
+    if (++yielded === 10) break;
+  }
+  await Promise.all(work);
+
+  assert.equal(peak, 2, 'No more than maxConcurrent guards should be alive at once');
+  assert.equal(pool.getMetrics().available, 5, 'Every guard was released');
+  assert.equal(pool.overReleaseCount(), 0);
+
+  await assert.rejects(pool.guards(0).next(), /maxConcurrent must be a positive integer/);
+  await pool.destroy();
+  assert.deepEqual(await pool.guards(1).next(), { done: true, value: undefined }, 'A destroyed pool yields nothing');
+});
+
+test('ObjectPool - destroy is idempotent and destroys each resource once', async () => {
+  let created = 0;
+  const destroyed: number[] = [];
+  const pool = createPool<TestResource>({
+    min: 0,
+    max: 3,
+    resourceFactory: async () => {
+      await new Promise((resolve) => setTimeout(resolve, 20));
+      return { id: created++, created: Date.now() };
+    },
+    resourceDestroyer: (res) => {
+      destroyed.push(res.id);
+    },
+  });
+  pool.add({ id: 100, created: Date.now() });
+  pool.add({ id: 101, created: Date.now() });
+  const { resource: taken } = await pool.takeAsync();
+
+  // Exhaust the pool so a scale-up is still in flight when destroy() runs
+  const held = pool.acquire()!;
+  const pending = pool.acquireAsync(1000).catch((error) => error);
+  const counts = await Promise.all([pool.destroy(), pool.destroy()]);
+  assert.deepEqual(counts, [1, 0], 'Only the first destroy releases anything');
+  assert.equal(await pool.destroy(), 0);
+  assert.match((await pending).message, /Pool is destroyed/);
+
+  // Let the in-flight factory finish: its resource is destroyed rather than leaked
+  await new Promise((resolve) => setTimeout(resolve, 40));
+  assert.equal(created, 1);
+  assert.deepEqual(destroyed, [held.id, 0], 'Every pooled resource is destroyed exactly once');
+  assert.ok(!destroyed.includes(taken.id), 'Taken resources belong to the caller');
+  assert.deepEqual(pool.getMetrics(), { size: 0, available: 0, busy: 0, capacity: 3, pendingCreates: 0 });
+});
+
+test('ObjectPool - shrinking skips empty slots left on the free stack', async () => {
+  let created = 0;
+  let destroyed = 0;
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 3,
+    resizeCooldownMs: 10,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+  pool.add({ id: 100, created: Date.now() });
+  pool.add({ id: 101, created: Date.now() });
+
+  // Simulate an inconsistent release: a slot goes back on the stack after its resource left the pool
+  const { index } = await pool.takeAsync();
+  (pool as unknown as { pool: BasePool }).pool.release(index);
+  assert.equal(pool.getMetrics().size, 2);
+
+  pool.requestSize(1);
+  await new Promise((resolve) => setTimeout(resolve, 30));
+  assert.equal(destroyed, 1, 'A real resource is destroyed, the empty slot does not count');
+  assert.deepEqual(pool.getMetrics(), { size: 1, available: 1, busy: 0, capacity: 3, pendingCreates: 0 });
+
+  // Capacity is intact: the pool can still grow back to max with a distinct resource per slot
+  const held = [pool.acquire()!, await pool.acquireAsync(100), await pool.acquireAsync(100)];
+  assert.equal(new Set(held).size, 3);
+  assert.equal(pool.getMetrics().size, 3);
+  assert.equal(pool.acquire(), null);
+
+  held.forEach((res) => pool.release(res));
+  await pool.destroy();
+});
+
+test('ObjectPool - use holds the resource across awaits and releases it afterwards', async () => {
+  const pool = createPool<TestResource>({ min: 1, max: 1, resourceFactory: () => ({ id: 1, created: Date.now() }) });
+
+  const result = await pool.use(async (res) => {
+    await new Promise((resolve) => setTimeout(resolve, 20));
+    assert.equal(pool.getMetrics().busy, 1, 'The resource stays checked out while the callback awaits');
+    return res.id;
+  });
+  assert.equal(result, 1);
+  assert.equal(pool.getMetrics().available, 1, 'Released once the callback settles');
+
+  await assert.rejects(
+    pool.use(async () => {
+      await new Promise((resolve) => setTimeout(resolve, 5));
+      throw new Error('boom');
+    }),
+    /boom/,
+  );
+  assert.equal(pool.getMetrics().available, 1, 'Released when the callback rejects too');
+  await pool.destroy();
+});
+
+test('ObjectPool - onMemoryPressure sheds idle resources down to min', async () => {
+  let created = 0;
+  let destroyed = 0;
+  const pool = createPool<TestResource>({
+    min: 2,
+    max: 6,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+  for (let i = 0; i < 4; i++) pool.add({ id: 100 + i, created: Date.now() });
+  const held = [pool.acquire()!, pool.acquire()!, pool.acquire()!];
+
+  assert.equal(pool.onMemoryPressure(), 3, 'Only idle resources are removed');
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.equal(destroyed, 3);
+  assert.deepEqual(pool.getMetrics(), { size: 3, available: 0, busy: 3, capacity: 6, pendingCreates: 0 });
+
+  held.forEach((res) => pool.release(res));
+  assert.equal(pool.onMemoryPressure(), 1, 'Stops at min');
+  assert.equal(pool.onMemoryPressure(), 0);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.equal(pool.getMetrics().size, 2);
+  await pool.destroy();
+});
+
+test('ObjectPool - concurrent acquisitions on an empty pool create exactly max resources', async () => {
+  let calls = 0;
+  const pool = createPool({
+    min: 0,
+    max: 3,
+    resourceFactory: async () => {
+      const id = ++calls;
+      await new Promise((resolve) => setTimeout(resolve, 10));
+      return { id };
+    },
+  });
+
+  // Every acquirer holds its resource briefly, so the extra ones are served by releases
+  const ids = await Promise.all(
+    Array.from({ length: 10 }, () =>
+      pool.use(async (res) => {
+        await new Promise((resolve) => setTimeout(resolve, 5));
+        return res.id;
+      }, 1000),
+    ),
+  );
+
+  assert.equal(calls, 3, 'One factory call per slot, never more than max');
+  assert.deepEqual([...new Set(ids)].sort(), [1, 2, 3]);
+  assert.deepEqual(pool.getMetrics(), { size: 3, available: 3, busy: 0, capacity: 3, pendingCreates: 0 });
+  await pool.destroy();
+});
+
+test('ObjectPool - resetStats zeroes cumulative counters but not gauges', async () => {
+  const pool = createPool<TestResource>({ min: 2, max: 2, resourceFactory: () => ({ id: 1, created: Date.now() }) });
+  const [a, b] = [pool.acquire()!, pool.acquire()!];
+
+  const waiter = pool.acquireAsync(1000);
+  await new Promise((resolve) => setTimeout(resolve, 5));
+  pool.release(a);
+  await waiter;
+  pool.release(b);
+  pool.release(b);
+  assert.ok(pool.maxWaitMicros() > 0);
+  assert.equal(pool.overReleaseCount(), 1);
+
+  const before = pool.getMetrics();
+  pool.resetStats();
+  assert.equal(pool.maxWaitMicros(), 0);
+  assert.equal(pool.overReleaseCount(), 0);
+  assert.deepEqual(pool.getMetrics(), before, 'Gauges are unchanged');
+
+  pool.release(a);
+  await pool.destroy();
+});
+
+test('ObjectPool - acquire skips a slot that lost its resource and uses another one', async () => {
+  const pool = createPool<TestResource>({ min: 0, max: 3, resourceFactory: () => ({ id: -1, created: 0 }) });
+  pool.add({ id: 1, created: Date.now() });
+  pool.add({ id: 2, created: Date.now() });
+
+  // Force the broken state: the slot on top of the free stack no longer holds a resource
+  const { resource: taken, index } = await pool.takeAsync();
+  (pool as unknown as { pool: BasePool }).pool.release(index);
+
+  assert.equal(pool.skippedSlotCount(), 0);
+  const res = pool.acquire();
+  assert.ok(res, 'The other slot should be used instead of failing');
+  assert.notEqual(res, taken);
+  assert.equal(pool.skippedSlotCount(), 1, 'The empty slot is counted');
+  assert.equal(pool.acquire(), null, 'One resource is left, and it is checked out');
+  pool.release(res);
+  pool.resetStats();
+  assert.equal(pool.skippedSlotCount(), 0);
+  await pool.destroy();
+});
+
+test('ObjectPool - releaseTransform replaces resources on their way back', async () => {
+  type Token = { value: string; rotations: number };
+  let destroyed = 0;
+  const pool = createPool<Token>({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ value: 'token-0', rotations: 0 }),
+    releaseTransform: (token) => {
+      if (token.value === 'poison') throw new Error('cannot rotate');
+      return { value: `token-${token.rotations + 1}`, rotations: token.rotations + 1 };
+    },
+    resourceDestroyer: () => {
+      destroyed++;
+    },
+  });
+
+  const first = pool.acquire()!;
+  assert.equal(pool.tryRelease(first), 'accepted');
+  const second = pool.acquire()!;
+  assert.notEqual(second, first, 'The transformed resource takes over the slot');
+  assert.deepEqual(second, { value: 'token-1', rotations: 1 });
+  assert.equal(pool.tryRelease(first), 'rejected', 'The replaced resource no longer belongs to the pool');
+
+  pool.release(second);
+  assert.equal(pool.tryRelease(second), 'rejected', 'A second release cannot transform the slot again');
+  const third = pool.acquire()!;
+  assert.equal(third.rotations, 2);
+
+  third.value = 'poison';
+  assert.throws(() => pool.release(third), /cannot rotate/);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.equal(destroyed, 1, 'A resource whose transform threw is destroyed');
+  assert.equal(pool.getMetrics().size, 0);
+  await pool.destroy();
+});
+
+test('ObjectPool - releaseTransform replacements that cannot take over the slot are refused', async () => {
+  const other = createPool<TestResource>({ min: 1, max: 1, resourceFactory: () => ({ id: 99, created: 0 }) });
+  const pooled = other.acquire()!;
+  let replacement: TestResource = pooled;
+  const pool = createPool<TestResource>({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ id: 0, created: 0 }),
+    releaseTransform: () => replacement,
+  });
+
+  const original = pool.acquire()!;
+  assert.throws(() => pool.release(original), /already in a pool/);
+  assert.equal(pool.getMetrics().available, 1, 'The original went back instead');
+  assert.equal(pool.acquire(), original);
+
+  replacement = Object.freeze({ id: 1, created: 0 });
+  assert.throws(() => pool.release(original), /extensible object/);
+  assert.equal(pool.acquire(), original, 'Still the original, and still releasable');
+  replacement = original;
+  assert.equal(pool.tryRelease(original), 'accepted');
+
+  other.release(pooled);
+  assert.equal(other.acquire(), pooled, 'The other pool still owns its resource');
+  other.release(pooled);
+  await other.destroy();
+  await pool.destroy();
+});
+
+test('ObjectPool - evictionStrategy oldest sheds the resources released longest ago', async () => {
+  const destroyed: number[] = [];
+  const create = (evictionStrategy: 'newest' | 'oldest') => {
+    let next = 0;
+    const pool = createPool<TestResource>({
+      min: 1,
+      max: 4,
+      evictionStrategy,
+      resourceFactory: () => ({ id: next++, created: Date.now() }),
+      resourceDestroyer: (res) => {
+        destroyed.push(res.id);
+      },
+    });
+    for (let i = 1; i < 4; i++) pool.add({ id: next++, created: Date.now() });
+    // Release in a known order, 2 first and 0 last
+    const held = [pool.acquire()!, pool.acquire()!, pool.acquire()!, pool.acquire()!];
+    for (const id of [2, 3, 1, 0]) pool.release(held.find((res) => res.id === id)!);
+    return pool;
+  };
+
+  const oldest = create('oldest');
+  assert.equal(oldest.getConfig().evictionStrategy, 'oldest');
+  assert.equal(oldest.onMemoryPressure(), 3);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.deepEqual(destroyed, [2, 3, 1], 'Evicted in release order, the most recently used one stays');
+  assert.equal(oldest.acquire()!.id, 0);
+  await oldest.destroy();
+
+  destroyed.length = 0;
+  const newest = create('newest');
+  assert.equal(newest.onMemoryPressure(), 3);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.deepEqual(destroyed, [0, 1, 3], 'The default pops the most recently released first');
+  await newest.destroy();
+});
+
+test('ObjectPool - destroyWith runs the finalizer once per resource', async () => {
+  const finalized: number[] = [];
+  let destroyerCalls = 0;
+  const pool = createPool<TestResource>({
+    min: 3,
+    max: 3,
+    resourceFactory: (() => {
+      let id = 0;
+      return () => ({ id: id++, created: Date.now() });
+    })(),
+    resourceDestroyer: () => {
+      destroyerCalls++;
+    },
+  });
+  const held = pool.acquire()!;
+
+  const count = pool.destroyWith((res) => {
+    finalized.push(res.id);
+    if (res.id === 0) throw new Error('close failed');
+  });
+  assert.equal(count, 3, 'Checked-out resources are finalized too');
+  assert.deepEqual(finalized.sort(), [0, 1, 2], 'A throwing finalizer does not stop the rest');
+  assert.equal(destroyerCalls, 0, 'The finalizer replaces resourceDestroyer');
+  assert.equal(pool.destroyWith(() => finalized.push(-1)), 0);
+  assert.equal(await pool.destroy(), 0);
+  assert.equal(pool.tryRelease(held), 'dropped');
+});
+
+test('ObjectPool - admission controller sheds acquisitions once too many are pending', async () => {
+  const pool = createPool<TestResource>({ min: 1, max: 1, resourceFactory: () => ({ id: 1, created: Date.now() }) });
+  pool.setAdmissionController(({ pending, size }) => pending < size);
+  const held = pool.acquire()!;
+
+  const waiter = pool.acquireAsync(1000);
+  await assert.rejects(pool.acquireAsync(1000), { name: 'PoolError', kind: 'queueFull' });
+
+  pool.release(held);
+  assert.equal(await waiter, held, 'Admitted acquisitions are served as usual');
+  pool.release(held);
+  await pool.destroy();
+});
+
+test('ObjectPool - replaceExpired swaps old idle resources one-for-one', async () => {
+  let created = 0;
+  const pool = createPool<TestResource>({
+    min: 3,
+    max: 3,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+  const held = pool.acquire()!;
+  await new Promise((resolve) => setTimeout(resolve, 30));
+
+  const expired = await pool.replaceExpired(20);
+  assert.equal(expired.length, 2, 'Only idle resources are replaced');
+  assert.ok(!expired.includes(held), 'The checked-out resource is untouched');
+  assert.equal(created, 5, 'One replacement per expired resource');
+  assert.deepEqual(pool.getMetrics(), { size: 3, available: 2, busy: 1, capacity: 3, pendingCreates: 0 });
+  for (const res of expired) assert.equal(pool.tryRelease(res), 'rejected', 'Expired resources left the pool');
+
+  assert.deepEqual(await pool.replaceExpired(20), [], 'The replacements are fresh');
+  pool.release(held);
+  assert.deepEqual((await pool.replaceExpired(20)).map((res) => res.id), [held.id]);
+  await pool.destroy();
+});
+
+test('ObjectPool - peek reads a slot without taking it out of rotation', async () => {
+  const pool = createPool<TestResource>({ min: 2, max: 2, resourceFactory: () => ({ id: 7, created: Date.now() }) });
+  const before = pool.getMetrics();
+
+  const peeked = pool.peek(0)!;
+  assert.equal(peeked.id, 7);
+  assert.deepEqual(pool.getMetrics(), before, 'Peeking consumes nothing');
+  assert.equal(pool.peek(2), null);
+
+  const acquired = [pool.acquire(), pool.acquire()];
+  assert.ok(acquired.includes(peeked), 'A peeked resource can still be acquired');
+  assert.equal(pool.peek(0), peeked, 'Peeking works while the resource is checked out');
+  acquired.forEach((res) => pool.release(res!));
+  await pool.destroy();
+  assert.equal(pool.peek(0), null);
+});
+
+test('ObjectPool - replaceAll installs a new resource set once the old one is returned', async () => {
+  let created = 10;
+  const pool = createPool<TestResource>({
+    min: 2,
+    max: 4,
+    resourceFactory: () => ({ id: created++, created: Date.now() }),
+  });
+  const held = pool.acquire()!;
+  const fresh = [1, 2, 3].map((id) => ({ id, created: Date.now() }));
+
+  const replacing = pool.replaceAll(fresh, 1000);
+  await new Promise((resolve) => setTimeout(resolve, 20));
+  const late = pool.acquire()!;
+  assert.ok(late.id >= 10, 'The old set is served until the swap');
+  pool.release(late);
+  pool.release(held);
+
+  const old = await replacing;
+  assert.deepEqual(old.map((res) => res.id).sort(), [10, 11], 'Resolves with the old resources');
+  assert.equal(pool.tryRelease(held), 'rejected', 'Old resources left the pool');
+  assert.deepEqual(pool.getMetrics(), { size: 3, available: 3, busy: 0, capacity: 4, pendingCreates: 0 });
+  const ids = [pool.acquire()!, pool.acquire()!, pool.acquire()!].map((res) => res.id).sort();
+  assert.deepEqual(ids, [1, 2, 3], 'Only the new resources are handed out');
+
+  await assert.rejects(pool.replaceAll([{ id: 9, created: 0 }], 30), { name: 'PoolError', kind: 'timeout' });
+  await assert.rejects(pool.replaceAll(fresh), /not in a pool yet/);
+
+  // Rejected up front: the pool keeps serving its current set
+  const twice = { id: 20, created: 0 };
+  await assert.rejects(pool.replaceAll([twice, twice]), /same resource more than once/);
+  await assert.rejects(pool.replaceAll([Object.freeze({ id: 21, created: 0 })]), /extensible objects/);
+  await assert.rejects(pool.replaceAll([null as unknown as TestResource]), /extensible objects/);
+  assert.deepEqual(pool.getMetrics(), { size: 3, available: 0, busy: 3, capacity: 4, pendingCreates: 0 });
+  assert.equal(pool.tryRelease(fresh[0]), 'accepted', 'The installed set is intact');
+  await pool.destroy();
+});
@@ -1,6 +1,7 @@
 import { test } from 'node:test';
 import assert from 'node:assert';
-import { createEnginePool } from '../../src/index';
+import { createEnginePool, EnginePool, MAX_CAPACITY } from '../../src/index';
+import { OFFSET_COUNT } from '../../src/internal/base-pool';
 
 test('EnginePool - basic acquire and release', () => {
   const pool = createEnginePool(3);
@@ -262,3 +263,680 @@ test('EnginePool - concurrent use operations', async () => {
 
   pool.destroy();
 });
+
+test('EnginePool - acquireAsyncTiered resolves immediately when a slot is free', async () => {
+  const pool = createEnginePool(1);
+
+  const { idx, softTimedOut } = await pool.acquireAsyncTiered(50, 1000);
+  assert.equal(idx, 0, 'Should acquire the only slot');
+  assert.equal(softTimedOut, false, 'Immediate acquisition should not be flagged as slow');
+
+  pool.release(idx);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncTiered flags waits past the soft timeout', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  setTimeout(() => {
+    pool.release(held);
+  }, 100);
+
+  const { idx, softTimedOut } = await pool.acquireAsyncTiered(30, 1000);
+  assert.equal(idx, held, 'Should acquire the released slot');
+  assert.equal(softTimedOut, true, 'Wait between soft and hard timeout should be flagged');
+
+  pool.release(idx);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncTiered does not overtake queued fair waiters', async () => {
+  const pool = createEnginePool(1);
+  const other = EnginePool.attach(pool.getBuffer());
+  const held = other.acquire();
+  const waiter = pool.acquireAsync(1000);
+
+  // Pushed by another handle, so the slot sits on the stack until this handle's watcher serves it
+  other.release(held);
+  const tiered = pool.acquireAsyncTiered(0, 1000);
+  assert.equal(await waiter, held, 'The FIFO waiter gets the slot first');
+  pool.release(held);
+  assert.equal((await tiered).idx, held);
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncTiered fails after the hard timeout', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  await assert.rejects(() => pool.acquireAsyncTiered(20, 80), (err: Error) => err.message.includes('Timeout'));
+
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncMeta counts repeated acquisitions of a slot', async () => {
+  const pool = createEnginePool(1);
+
+  for (let i = 1; i <= 3; i++) {
+    const { idx, useCount, ageMs } = await pool.acquireAsyncMeta(1000);
+    assert.equal(idx, 0, 'Single-slot pool should always hand out slot 0');
+    assert.equal(useCount, i, 'Use count should increment on every acquire');
+    assert.ok(ageMs >= 0, 'Age should be non-negative');
+    pool.release(idx);
+  }
+
+  // Sync acquisitions count towards the same slot
+  pool.release(pool.acquire());
+  const { idx, useCount } = await pool.acquireAsyncMeta();
+  assert.equal(useCount, 5);
+
+  pool.release(idx);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncMeta reports pool pressure after the acquisition', async () => {
+  const pool = createEnginePool(3);
+
+  const idle = await pool.acquireAsyncMeta();
+  assert.equal(idle.availableAfter, 2);
+  assert.equal(idle.pendingAfter, 0);
+
+  // Saturate the pool and queue three more acquisitions behind it
+  const held = [pool.acquire(), pool.acquire()];
+  const queued = [pool.acquireAsyncMeta(1000), pool.acquireAsyncMeta(1000), pool.acquireAsyncMeta(1000)];
+  pool.release(idle.idx);
+  const first = await queued[0];
+  assert.equal(first.availableAfter, 0, 'A saturated pool has nothing left');
+  assert.equal(first.pendingAfter, 2, 'Two acquisitions are still queued');
+
+  pool.release(held[0]);
+  pool.release(held[1]);
+  const rest = await Promise.all(queued.slice(1));
+  assert.equal(rest[1].pendingAfter, 0);
+  pool.destroy();
+});
+
+test('EnginePool - validateCapacity accepts the supported range', () => {
+  assert.equal(EnginePool.maxAllowedCapacity, MAX_CAPACITY);
+
+  assert.doesNotThrow(() => EnginePool.validateCapacity(1));
+  assert.doesNotThrow(() => EnginePool.validateCapacity(4096));
+  assert.doesNotThrow(() => EnginePool.validateCapacity(MAX_CAPACITY));
+
+  // Whatever validateCapacity accepts, the constructor can build
+  const largest = createEnginePool(MAX_CAPACITY);
+  assert.equal(largest.size, MAX_CAPACITY);
+  largest.destroy();
+});
+
+test('EnginePool - validateCapacity rejects out of range capacities', () => {
+  assert.throws(() => EnginePool.validateCapacity(0), /capacity must be at least 1/);
+  assert.throws(() => EnginePool.validateCapacity(MAX_CAPACITY + 1), /capacity cannot exceed 65535/);
+  assert.throws(() => EnginePool.validateCapacity(1.5), /capacity must be an integer/);
+
+  // The constructor applies the same rules
+  assert.throws(() => createEnginePool(0), /capacity must be at least 1/);
+});
+
+test('EnginePool - reset reclaims all slots of an exhausted pool', () => {
+  const pool = createEnginePool(4);
+
+  for (let i = 0; i < 4; i++) pool.acquire();
+  assert.equal(pool.acquire(), -1, 'Pool should be exhausted');
+
+  pool.reset();
+  assert.equal(pool.available, 4, 'All slots should be available after reset');
+
+  const slots = new Set<number>();
+  for (let i = 0; i < 4; i++) slots.add(pool.acquire());
+  assert.deepEqual([...slots].sort(), [0, 1, 2, 3], 'Every slot should be acquirable exactly once');
+  assert.equal(pool.acquire(), -1);
+
+  pool.destroy();
+});
+
+test('EnginePool - reset serves queued waiters', async () => {
+  const pool = createEnginePool(1);
+  pool.acquire();
+
+  const waiter = pool.acquireAsync(1000);
+  pool.reset();
+
+  assert.equal(await waiter, 0, 'Waiter should receive the reclaimed slot');
+  pool.destroy();
+});
+
+test('EnginePool - releaseChecked returns an async-acquired slot', async () => {
+  const pool = createEnginePool(3);
+
+  const idx = await pool.acquireAsync(1000);
+  assert.equal(pool.isCheckedOut(idx), true);
+  assert.equal(pool.availableCount(), 2);
+
+  pool.releaseChecked(idx);
+  assert.equal(pool.isCheckedOut(idx), false);
+  assert.equal(pool.availableCount(), 3, 'Available count should be restored');
+
+  pool.destroy();
+});
+
+test('EnginePool - releaseChecked rejects slots that are not checked out', () => {
+  const pool = createEnginePool(2);
+  const idx = pool.acquire();
+  pool.releaseChecked(idx);
+
+  assert.throws(() => pool.releaseChecked(idx), /Slot \d is not checked out/);
+  assert.throws(() => pool.releaseChecked(5), /Slot 5 is out of range \(capacity 2\)/);
+  assert.equal(pool.availableCount(), 2, 'Rejected releases should not change the count');
+
+  pool.destroy();
+});
+
+test('EnginePool - zero timeout waits instead of failing immediately', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  // acquire() is the immediate try
+  assert.equal(pool.acquire(), -1);
+
+  const waiter = pool.acquireAsync(0);
+  setTimeout(() => pool.release(held), 30);
+  assert.equal(await waiter, held, 'A zero timeout should wait for the next release');
+
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - maxWaitMicros records the longest queued wait', async () => {
+  const pool = createEnginePool(1);
+  assert.equal(pool.maxWaitMicros(), 0);
+
+  const held = pool.acquire();
+  setTimeout(() => pool.release(held), 150);
+  const idx = await pool.acquireAsync(2000);
+
+  assert.ok(pool.maxWaitMicros() >= 140000, `Expected a ~150ms wait, got ${pool.maxWaitMicros()}us`);
+
+  // Immediate acquisitions don't lower the high-water mark
+  pool.release(idx);
+  const peak = pool.maxWaitMicros();
+  pool.release(await pool.acquireAsync(100));
+  assert.equal(pool.maxWaitMicros(), peak);
+
+  pool.resetWaitStats();
+  assert.equal(pool.maxWaitMicros(), 0);
+
+  pool.destroy();
+});
+
+test('EnginePool - acquireManyAsync blocks a heavy acquisition until enough slots free', async () => {
+  const pool = createEnginePool(4);
+  const small = [pool.acquire(), pool.acquire(), pool.acquire()];
+
+  let settled = false;
+  const pending = pool.acquireManyAsync(3, 2000).finally(() => {
+    settled = true;
+  });
+
+  pool.release(small[0]);
+  await new Promise((resolve) => setImmediate(resolve));
+  assert.equal(settled, false, 'Two free slots are not enough for a weight of 3');
+
+  pool.release(small[1]);
+  const heavy = await pending;
+  assert.equal(new Set(heavy).size, 3, 'Heavy acquisition should hold three distinct slots');
+  assert.equal(pool.available, 0);
+
+  for (const idx of [...heavy, small[2]]) pool.release(idx);
+  pool.destroy();
+});
+
+test('EnginePool - acquireManyAsync releases partial batches on timeout', async () => {
+  const pool = createEnginePool(3);
+  const held = [pool.acquire(), pool.acquire()];
+
+  await assert.rejects(() => pool.acquireManyAsync(2, 50), /Timeout/);
+  assert.equal(pool.available, 1, 'The slot taken before the timeout should be returned');
+
+  await assert.rejects(() => pool.acquireManyAsync(4), /count must be between 1 and 3/);
+
+  for (const idx of held) pool.release(idx);
+  pool.destroy();
+});
+
+test('EnginePool - acquireManyAsync times out while queued behind another batch', async () => {
+  const pool = createEnginePool(2);
+  const held = [pool.acquire(), pool.acquire()];
+
+  const first = pool.acquireManyAsync(2, 0);
+  const start = Date.now();
+  await assert.rejects(pool.acquireManyAsync(1, 50), { name: 'PoolError', kind: 'timeout', timeoutMs: 50 });
+  assert.ok(Date.now() - start < 400, 'The timeout counts while waiting for the earlier batch');
+
+  for (const idx of held) pool.release(idx);
+  assert.equal((await first).length, 2);
+  assert.equal(pool.available, 0, 'The timed-out batch took nothing once its turn came');
+  await new Promise((resolve) => setTimeout(resolve, 10));
+  assert.equal(pool.available, 0);
+  pool.destroy();
+});
+
+test('EnginePool - acquireManyAsync returns distinct indices for a full-pool batch', async () => {
+  const pool = createEnginePool(64);
+  const scattered = Array.from({ length: 16 }, () => pool.acquire());
+  // Put the free stack out of index order before the batch takes everything
+  for (const idx of scattered.reverse()) pool.releaseBack(idx);
+
+  const batch = await pool.acquireManyAsync(64, 1000);
+  assert.equal(batch.length, 64);
+  assert.equal(new Set(batch).size, 64, 'Every index appears once');
+  assert.equal(pool.available, 0);
+  for (const idx of batch) pool.release(idx);
+  assert.equal(pool.overReleaseCount(), 0);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncAbortable leaves the queue when its signal aborts', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  const controller = new AbortController();
+  const pending = pool.acquireAsyncAbortable(controller.signal, 5000);
+  const other = pool.acquireAsync(5000);
+  assert.equal(pool.pendingCount, 2);
+
+  const start = Date.now();
+  setTimeout(() => controller.abort(new Error('Request cancelled')), 20);
+  await assert.rejects(pending, /Request cancelled/);
+  assert.ok(Date.now() - start < 1000, 'Abort should reject promptly, not at the timeout');
+  assert.equal(pool.pendingCount, 1, 'Aborted waiter should be removed from the queue');
+
+  pool.release(held);
+  assert.equal(await other, held, 'The next waiter should get the slot');
+  assert.equal(pool.pendingCount, 0);
+
+  await assert.rejects(pool.acquireAsyncAbortable(AbortSignal.abort()), { name: 'AbortError' });
+
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - over-release is dropped instead of duplicating a slot', () => {
+  const released: number[] = [];
+  const pool = createEnginePool(2, { observer: { onRelease: (slot) => released.push(slot) } });
+  const idx = pool.acquire();
+
+  pool.release(idx);
+  pool.release(idx);
+  assert.equal(pool.overReleaseCount(), 1, 'The second release should be flagged');
+  assert.equal(pool.available, 2, 'Availability should not exceed capacity');
+  assert.deepEqual(released, [idx], 'The observer only sees the release that went through');
+  pool.releaseBack(idx);
+  assert.throws(() => pool.releaseChecked(idx), /not checked out/);
+  assert.deepEqual(released, [idx]);
+
+  const a = pool.acquire();
+  const b = pool.acquire();
+  assert.notEqual(a, b, 'The double-released slot must not be handed out twice');
+  assert.equal(pool.acquire(), -1, 'Capacity should be unchanged');
+
+  pool.release(a);
+  pool.release(b);
+  assert.equal(pool.available, 2, 'Capacity should not erode either');
+  assert.equal(pool.overReleaseCount(), 2);
+  pool.verify();
+  pool.destroy();
+});
+
+test('EnginePool - checkInvariants holds across random acquire/release sequences', () => {
+  const capacity = 8;
+  const pool = createEnginePool(capacity, { checkInvariants: true });
+  const held: number[] = [];
+
+  // Deterministic Park-Miller generator so failures are reproducible
+  let seed = 42;
+  const random = () => (seed = (seed * 16807) % 2147483647) / 2147483647;
+
+  for (let step = 0; step < 5000; step++) {
+    const roll = random();
+    if (roll < 0.45) {
+      const idx = pool.acquire();
+      if (idx !== -1) held.push(idx);
+    } else if (roll < 0.9 && held.length > 0) {
+      const [idx] = held.splice(Math.floor(random() * held.length), 1);
+      pool.release(idx);
+    } else {
+      // Release a slot that is already free; it must be dropped, not pushed
+      const free = Array.from({ length: capacity }, (_, i) => i).filter((i) => !held.includes(i));
+      if (free.length > 0) pool.release(free[Math.floor(random() * free.length)]);
+    }
+
+    assert.equal(pool.available + held.length, capacity, `Accounting drifted at step ${step}`);
+  }
+
+  pool.verify();
+  pool.destroy();
+});
+
+test('EnginePool - checkInvariants reports a free count above capacity', () => {
+  const pool = createEnginePool(2, { checkInvariants: true });
+  const idx = pool.acquire();
+
+  // Simulate a buggy writer inflating COUNT in the shared buffer
+  new Int32Array(pool.getBuffer())[OFFSET_COUNT] = 2;
+  assert.throws(() => pool.release(idx), /Pool invariant violated: 3 slots available but capacity is 2/);
+});
+
+test('EnginePool - watchAvailable reports every change to the available count', async () => {
+  const pool = createEnginePool(3);
+  const seen: number[] = [];
+  const unsubscribe = pool.watchAvailable((available) => seen.push(available));
+
+  const a = pool.acquire();
+  const b = await pool.acquireAsync();
+  pool.release(a);
+  pool.release(a); // dropped double release: no change
+  pool.release(b);
+  assert.deepEqual(seen, [2, 1, 2, 3]);
+
+  // A release handed straight to a waiter leaves the count unchanged
+  const held = [pool.acquire(), pool.acquire(), pool.acquire()];
+  const waiter = pool.acquireAsync(1000);
+  pool.release(held[0]);
+  await waiter;
+  assert.deepEqual(seen.slice(4), [2, 1, 0]);
+
+  unsubscribe();
+  pool.release(held[0]);
+  assert.equal(seen.length, 7, 'Unsubscribed listeners are not called');
+  pool.destroy();
+});
+
+test('EnginePool - availableIndices snapshots the free slots', () => {
+  const pool = createEnginePool(5);
+  assert.deepEqual(pool.availableIndices(), [0, 1, 2, 3, 4]);
+
+  const a = pool.acquire();
+  const b = pool.acquire();
+  const expected = [0, 1, 2, 3, 4].filter((i) => i !== a && i !== b);
+  assert.deepEqual(pool.availableIndices(), expected);
+  assert.equal(pool.availableIndices().length, pool.available);
+
+  pool.release(a);
+  assert.deepEqual(pool.availableIndices(), [...expected, a].sort());
+
+  pool.destroy();
+  assert.deepEqual(pool.availableIndices(), [], 'Destroy empties the free stack');
+});
+
+test('EnginePool - acquireAnyOfAsync only returns candidate slots', async () => {
+  const pool = createEnginePool(6, { fair: false });
+  const candidates = [1, 3];
+
+  const first = await pool.acquireAnyOfAsync(candidates);
+  const second = await pool.acquireAnyOfAsync(candidates);
+  assert.deepEqual([first, second].sort(), candidates);
+  assert.equal(pool.available, 4, 'Slots popped on the way are put back');
+  assert.equal(pool.isCheckedOut(first) && pool.isCheckedOut(second), true);
+
+  let resolved: number | null = null;
+  const waiter = pool.acquireAnyOfAsync(candidates, 1000).then((idx) => (resolved = idx));
+  const other = pool.acquire();
+  pool.release(other);
+  await new Promise((resolve) => setTimeout(resolve, 20));
+  assert.equal(resolved, null, 'Releasing a slot outside the set does not satisfy the wait');
+
+  pool.release(3);
+  await waiter;
+  assert.equal(resolved, 3);
+  for (let i = 0; i < 50; i++) {
+    const idx = await pool.acquireAnyOfAsync([1, 3, 5]);
+    assert.ok([1, 3, 5].includes(idx));
+    pool.release(idx);
+  }
+
+  await assert.rejects(pool.acquireAnyOfAsync([1, 3], 20), /Timeout acquiring resource \(20ms\)/);
+  await assert.rejects(pool.acquireAnyOfAsync([2, 9]), /Slot 9 is out of range/);
+  await assert.rejects(pool.acquireAnyOfAsync([]), /candidates must not be empty/);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAnyOfAsync keeps the order of the slots it lifts off', async () => {
+  const pool = createEnginePool(5, { fair: false });
+  const order: number[] = [];
+  for (let idx = pool.acquire(); idx !== -1; idx = pool.acquire()) order.push(idx);
+  for (let i = order.length - 1; i >= 0; i--) pool.release(order[i]);
+
+  const deep = order[3];
+  assert.equal(await pool.acquireAnyOfAsync([deep]), deep);
+  const rest: number[] = [];
+  for (let idx = pool.acquire(); idx !== -1; idx = pool.acquire()) rest.push(idx);
+  assert.deepEqual(rest, order.filter((idx) => idx !== deep), 'Slots above the candidate go back in order');
+  pool.destroy();
+});
+
+test('EnginePool - acquireInto fills a caller-provided array', () => {
+  const pool = createEnginePool(5);
+  const out: number[] = [];
+
+  assert.equal(pool.acquireInto(out, 3), 3);
+  assert.equal(out.length, 3);
+  assert.equal(pool.available, 2, 'Accounting matches the number acquired');
+
+  assert.equal(pool.acquireInto(out, 10), 2, 'Stops once the pool is empty');
+  assert.equal(out.length, 5);
+  assert.deepEqual([...out].sort(), [0, 1, 2, 3, 4]);
+  assert.equal(pool.acquireInto(out, 1), 0);
+
+  out.forEach((idx) => pool.release(idx));
+  out.length = 0;
+  assert.equal(pool.acquireInto(out, 0), 0);
+  assert.equal(pool.available, 5);
+  pool.destroy();
+});
+
+test('EnginePool - rejectPending fails queued waiters and keeps the pool usable', async () => {
+  let timeouts = 0;
+  const pool = createEnginePool(1, { observer: { onTimeout: () => timeouts++ } });
+  const held = pool.acquire();
+
+  const reason = new Error('shedding load');
+  const waiters = [pool.acquireAsync(1000), pool.acquireAsync(), pool.acquireAsync(1000)];
+  assert.equal(pool.rejectPending(reason), 3);
+  for (const waiter of waiters) await assert.rejects(waiter, (error) => error === reason);
+  assert.equal(pool.pendingCount, 0);
+  assert.equal(timeouts, 0, 'Shed waiters are not reported as timeouts');
+  assert.equal(pool.rejectPending(reason), 0);
+
+  const next = pool.acquireAsync(1000);
+  pool.release(held);
+  assert.equal(await next, held, 'The pool still serves new waiters');
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - acquireForKeyAsync reuses the slot a key had last time', async () => {
+  const pool = createEnginePool(4);
+
+  const first = await pool.acquireForKeyAsync('session-a');
+  pool.release(first);
+  // Bury the slot under another one on the free stack
+  assert.equal(pool.acquire(), first);
+  const other = pool.acquire();
+  pool.release(first);
+  pool.release(other);
+
+  const again = await pool.acquireForKeyAsync('session-a');
+  assert.equal(again, first, 'A free preferred slot is handed back');
+
+  // While the preferred slot is held, fall back to another one and remember it
+  const fallback = await pool.acquireForKeyAsync('session-a', 100);
+  assert.notEqual(fallback, first);
+  pool.release(fallback);
+  pool.release(again);
+  assert.equal(await pool.acquireForKeyAsync('session-a'), fallback, 'The mapping follows the fallback');
+
+  const otherKey = await pool.acquireForKeyAsync('session-b');
+  assert.notEqual(otherKey, fallback, 'Keys only stick to slots that are free');
+  pool.destroy();
+});
+
+test('EnginePool - acquireForKeyAsync keeps one key per slot and respects the fair queue', async () => {
+  const pool = createEnginePool(1);
+
+  // Thousands of one-off keys share the only slot: each binding replaces the last
+  for (let i = 0; i < 1000; i++) pool.release(await pool.acquireForKeyAsync(`session-${i}`));
+  // @ts-ignore
+  assert.equal(pool.affinity.size, 1, 'The mapping is bounded by the pool size');
+  // @ts-ignore
+  assert.deepEqual([...pool.affinity.keys()], ['session-999']);
+
+  // The sticky slot sits on the stack, pushed by another handle, while a FIFO waiter is queued
+  const other = EnginePool.attach(pool.getBuffer());
+  const held = other.acquire();
+  const waiter = pool.acquireAsync(1000);
+  other.release(held);
+  const sticky = pool.acquireForKeyAsync('session-999', 1000);
+  assert.equal(await waiter, held, 'The queued waiter is served first');
+  pool.release(held);
+  assert.equal(await sticky, held);
+  pool.release(held);
+  pool.destroy();
+});
+
+test('EnginePool - destroy can be called twice', () => {
+  const pool = createEnginePool(3);
+  const idx = pool.acquire();
+  pool.destroy();
+  pool.destroy();
+  pool.release(idx);
+  assert.equal(pool.available, 0, 'Counters stay consistent after a repeated destroy');
+  assert.equal(pool.acquire(), -1);
+});
+
+test('EnginePool - releaseFront and releaseBack choose when a slot is reused', () => {
+  const pool = createEnginePool(4);
+  const [a, b] = [pool.acquire(), pool.acquire()];
+
+  pool.releaseFront(a);
+  assert.equal(pool.acquire(), a, 'A front-released slot is handed out next');
+
+  pool.releaseBack(a);
+  pool.releaseBack(b);
+  const order = [pool.acquire(), pool.acquire(), pool.acquire(), pool.acquire()];
+  assert.deepEqual(order.slice(2), [a, b], 'Back-released slots come last, in release order');
+  assert.equal(new Set(order).size, 4);
+  assert.equal(pool.overReleaseCount(), 0);
+
+  order.forEach((idx) => pool.release(idx));
+  pool.releaseBack(order[0]);
+  assert.equal(pool.overReleaseCount(), 1, 'Double releases are still dropped');
+  assert.equal(pool.available, 4);
+  pool.destroy();
+});
+
+test('EnginePool - releaseBack and acquireAnyOfAsync only report the restored stack', async () => {
+  const pool = createEnginePool(6, { fair: false });
+  const held = pool.acquire();
+  const seen: number[] = [];
+  const unsubscribe = pool.watchAvailable((available) => seen.push(available));
+
+  pool.releaseBack(held);
+  assert.deepEqual(seen, [6], 'Lifting the free slots is not visible to watchers');
+
+  const deep = await pool.acquireAnyOfAsync([held]);
+  assert.equal(deep, held);
+  assert.deepEqual(seen, [6, 5]);
+  assert.equal(pool.isCheckedOut(deep), true);
+  assert.equal(pool.available, 5);
+
+  unsubscribe();
+  pool.release(deep);
+  pool.destroy();
+});
+
+test('EnginePool - admission controller rejects acquisitions past a queue limit', async () => {
+  const pool = createEnginePool(2);
+  const seen: { available: number; pending: number; size: number }[] = [];
+  pool.setAdmissionController((state) => {
+    seen.push(state);
+    return state.pending < 2;
+  });
+  const held = [pool.acquire(), pool.acquire()];
+
+  const queued = [pool.acquireAsync(1000), pool.acquireAsync(1000)];
+  await assert.rejects(pool.acquireAsync(1000), { name: 'PoolError', kind: 'queueFull' });
+  assert.deepEqual(seen, [
+    { available: 0, pending: 0, size: 2 },
+    { available: 0, pending: 1, size: 2 },
+    { available: 0, pending: 2, size: 2 },
+  ]);
+  assert.equal(pool.pendingCount, 2, 'The rejected acquire never joined the queue');
+
+  pool.setAdmissionController(null);
+  const third = pool.acquireAsync(1000);
+  held.forEach((idx) => pool.release(idx));
+  const [a, b] = await Promise.all(queued);
+  pool.release(a);
+  await third;
+  assert.equal(seen.length, 3, 'Acquires that find a free slot never reach the controller');
+  pool.release(b);
+  pool.destroy();
+});
+
+test('EnginePool - release rejects out-of-range indices without touching the pool', () => {
+  const pool = createEnginePool(3);
+  const held = pool.acquire();
+
+  for (const idx of [3, -1, 1.5, NaN]) {
+    assert.throws(() => pool.release(idx), { name: 'PoolError', kind: 'invalidSlot' });
+  }
+  assert.throws(() => pool.releaseBack(100), { name: 'PoolError', kind: 'invalidSlot', slot: 100 });
+  assert.equal(pool.availableCount(), 2, 'The count is unchanged');
+  assert.equal(pool.overReleaseCount(), 0, 'Garbage indices are errors, not over-releases');
+  assert.doesNotThrow(() => pool.verify());
+
+  pool.release(held);
+  assert.equal(pool.availableCount(), 3);
+  pool.destroy();
+});
+
+test('EnginePool - targeted acquires never hand out a slot that is already held', async () => {
+  const pool = createEnginePool(2);
+  const held = pool.acquire();
+
+  await assert.rejects(pool.acquireAnyOfAsync([held], 30), { name: 'PoolError', kind: 'timeout' });
+  const key = await pool.acquireForKeyAsync('session');
+  pool.release(held);
+  pool.release(key);
+
+  // The key's slot is held now: the preferred lookup falls through instead of marking it again
+  const taken = pool.acquire();
+  assert.equal(taken, key);
+  const other = await pool.acquireForKeyAsync('session', 100);
+  assert.notEqual(other, taken);
+  assert.equal(pool.inUse, 2);
+  assert.doesNotThrow(() => pool.verify());
+  pool.release(taken);
+  pool.release(other);
+  pool.destroy();
+});
+
+test('EnginePool - acquireAsyncWithPosition reports each waiter its place in line', async () => {
+  const pool = createEnginePool(1);
+  const positions: number[] = [];
+
+  const first = await pool.acquireAsyncWithPosition((position) => positions.push(position));
+  assert.deepEqual(positions, [], 'A caller served right away is never enqueued');
+
+  const waiters = [1, 2, 3].map(() => pool.acquireAsyncWithPosition((position) => positions.push(position), 1000));
+  assert.deepEqual(positions, [1, 2, 3]);
+
+  pool.release(first);
+  for (const waiter of waiters) pool.release(await waiter);
+  assert.equal(pool.pendingCount, 0);
+  pool.destroy();
+});
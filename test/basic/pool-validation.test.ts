@@ -87,12 +87,12 @@ test('Validation - max less than 1 throws', () => {
   );
 });
 
-test('Validation - max exceeding INT32_MAX throws', () => {
+test('Validation - max exceeding MAX_CAPACITY throws', () => {
   assert.throws(
     () => {
       createPool({
         min: 1,
-        max: 2147483648, // INT32_MAX + 1
+        max: 65536, // MAX_CAPACITY + 1
         resourceFactory: () => ({ id: 1 }),
       });
     },
@@ -1,6 +1,8 @@
 import { test } from 'node:test';
 import assert from 'node:assert';
-import { createPool } from '../../src/index';
+import { spawnSync } from 'node:child_process';
+import path from 'node:path';
+import { createEnginePool, createPool } from '../../src/index';
 
 interface TestResource {
   id: number;
@@ -300,3 +302,133 @@ test('Concurrency - MetricAccuracy during concurrent operations', async () => {
 
   await pool.destroy();
 });
+
+test('Concurrency - pendingCount returns to 0 after many waiters complete or time out', async () => {
+  const pool = createEnginePool(4);
+
+  // Many more acquirers than slots; the short-timeout ones give up while queued
+  const acquirers = Array.from({ length: 200 }, async (_, i) => {
+    try {
+      const idx = await pool.acquireAsync(i % 5 === 0 ? 5 : 2000);
+      await new Promise((resolve) => setImmediate(resolve));
+      pool.release(idx);
+      return true;
+    } catch {
+      return false;
+    }
+  });
+
+  assert.ok(pool.pendingCount > 0, 'Acquirers beyond capacity should be pending');
+  const outcomes = await Promise.all(acquirers);
+
+  assert.ok(outcomes.some((ok) => ok));
+  assert.equal(pool.pendingCount, 0, 'No waiter should remain pending');
+  assert.equal(pool.available, 4, 'Every slot should be back');
+
+  pool.destroy();
+});
+
+test('Concurrency - useEach fans out inputs over a small pool', async () => {
+  let nextId = 0;
+  const pool = createPool({ min: 2, max: 2, resourceFactory: () => ({ id: nextId++ }) });
+
+  let active = 0;
+  let peak = 0;
+  const results = await pool.useEach([1, 2, 3, 4, 5, 6], async (resource, input) => {
+    active++;
+    peak = Math.max(peak, active);
+    await new Promise((resolve) => setTimeout(resolve, 5));
+    active--;
+    return { input, doubled: input * 2, id: resource.id };
+  });
+
+  assert.deepEqual(results.map((r) => r.doubled), [2, 4, 6, 8, 10, 12], 'Results should keep input order');
+  assert.ok(results.every((r) => r.id === 0 || r.id === 1), 'Tasks should run on pooled resources');
+  assert.equal(peak, 2, 'No more tasks than resources should run at once');
+  assert.equal(pool.getMetrics().available, 2, 'Every resource should be released afterwards');
+
+  await pool.destroy();
+});
+
+test('Concurrency - destroy rejects every pending waiter', async () => {
+  const pool = createEnginePool(1);
+  pool.acquire();
+
+  const waiters = Array.from({ length: 5 }, () => pool.acquireAsync(5000));
+  assert.equal(pool.pendingCount, 5);
+
+  setTimeout(() => pool.destroy(), 10);
+  const outcomes = await Promise.allSettled(waiters);
+
+  for (const outcome of outcomes) {
+    assert.equal(outcome.status, 'rejected');
+    assert.match((outcome as PromiseRejectedResult).reason.message, /Pool is destroyed/);
+  }
+  assert.equal(pool.pendingCount, 0, 'No waiter should be left pending');
+  await assert.rejects(() => pool.acquireAsync(100), /Pool is destroyed/);
+});
+
+test('Concurrency - ObjectPool destroy rejects pending acquireAsync calls', async () => {
+  const pool = createPool({ min: 1, max: 1, resourceFactory: () => ({ id: 0 }) });
+  pool.acquire();
+
+  const waiters = Array.from({ length: 3 }, () => pool.acquireAsync(5000));
+  setTimeout(() => pool.destroy(), 10);
+
+  const outcomes = await Promise.allSettled(waiters);
+  assert.ok(
+    outcomes.every((o) => o.status === 'rejected' && /Pool is destroyed/.test(o.reason.message)),
+    'Every waiter should fail with the closed error',
+  );
+});
+
+test('Concurrent - unref pools reject pending waiters at process exit instead of hanging', () => {
+  const script = path.join(__dirname, 'workers', 'shutdown-child.ts');
+  const child = spawnSync(process.execPath, [...process.execArgv, script], { encoding: 'utf8', timeout: 10000 });
+
+  assert.equal(child.error, undefined, 'Child process should exit on its own');
+  assert.equal(child.status, 0, child.stderr);
+  const reports = child.stdout.trim().split('\n').map((line) => JSON.parse(line));
+  assert.deepEqual(reports.map((r) => r.pool).sort(), ['engine', 'object']);
+  for (const { kind, message, idle } of reports) {
+    assert.equal(kind, 'closed');
+    assert.equal(message, 'Pool is closed: the process is exiting');
+    assert.equal(idle, true, 'Held resources can still be released');
+  }
+  assert.deepEqual(reports.find((r) => r.pool === 'object').destroyed, [0], 'destroy() still runs the destroyer');
+});
+
+test('Concurrent - waitUntilIdle resolves once delayed releases and waiters settle', async () => {
+  const engine = createEnginePool(2);
+  const a = engine.acquire();
+  const b = engine.acquire();
+  const queued = engine.acquireAsync(1000).then((idx) => setTimeout(() => engine.release(idx), 30));
+  setTimeout(() => engine.release(a), 20);
+  setTimeout(() => engine.release(b), 40);
+
+  const start = Date.now();
+  await engine.waitUntilIdle(2000);
+  assert.ok(Date.now() - start >= 40, 'Should wait for the last release');
+  assert.equal(engine.inUse, 0);
+  assert.equal(engine.pendingCount, 0);
+  await queued;
+  engine.destroy();
+
+  const pool = createPool<TestResource>({ min: 1, max: 2, resourceFactory: () => ({ id: 0, useCount: 0 }) });
+  const res = pool.acquire()!;
+  setTimeout(() => pool.release(res), 30);
+  await pool.waitUntilIdle(2000);
+  assert.equal(pool.getMetrics().busy, 0);
+  await pool.destroy();
+});
+
+test('Concurrent - waitUntilIdle times out while a resource is held', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  await assert.rejects(pool.waitUntilIdle(30), { kind: 'timeout', message: /idle \(30ms\)/ });
+
+  pool.release(held);
+  await pool.waitUntilIdle(30);
+  pool.destroy();
+});
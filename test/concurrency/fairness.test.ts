@@ -0,0 +1,154 @@
+import { test } from 'node:test';
+import assert from 'node:assert';
+import { createEnginePool, createPool } from '../../src/index';
+
+test('Fairness - EnginePool serves async waiters in FIFO order', async () => {
+  const pool = createEnginePool(1);
+  assert.equal(pool.isFair(), true, 'Pools should be fair by default');
+
+  const held = pool.acquire();
+  const order: number[] = [];
+
+  // Queue waiters in a known order; each releases as soon as it is served
+  const waiters = Array.from({ length: 5 }, (_, i) =>
+    pool.acquireAsync(1000).then((idx) => {
+      order.push(i);
+      pool.release(idx);
+    }),
+  );
+
+  pool.release(held);
+  await Promise.all(waiters);
+
+  assert.deepEqual(order, [0, 1, 2, 3, 4], 'Waiters should be served in arrival order');
+  assert.equal(pool.available, 1, 'Slot should be back in the pool');
+
+  pool.destroy();
+});
+
+test('Fairness - waiters are ordered by arrival only, not by their timeouts', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+  const order: number[] = [];
+
+  // Mixed and equal timeouts must not reorder the queue
+  const timeouts = [5000, 1000, 1000, 0, 3000, 1000];
+  const waiters = timeouts.map((timeout, i) =>
+    pool.acquireAsync(timeout).then((idx) => {
+      order.push(i);
+      pool.release(idx);
+    }),
+  );
+
+  pool.release(held);
+  await Promise.all(waiters);
+
+  assert.deepEqual(order, [0, 1, 2, 3, 4, 5], 'Waiters should be served in arrival order');
+  pool.destroy();
+});
+
+test('Fairness - sync acquire cannot overtake queued waiters', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  const waiter = pool.acquireAsync(1000);
+  pool.release(held);
+
+  assert.equal(pool.acquire(), -1, 'Released slot should go to the queued waiter');
+  assert.equal(await waiter, held, 'Waiter should receive the released slot');
+
+  pool.release(held);
+  pool.destroy();
+});
+
+test('Fairness - unfair pool lets sync acquire take a released slot', async () => {
+  const pool = createEnginePool(1, { fair: false });
+  assert.equal(pool.isFair(), false);
+
+  const held = pool.acquire();
+  const waiter = pool.acquireAsync(1000);
+
+  pool.release(held);
+  const stolen = pool.acquire();
+  assert.equal(stolen, held, 'Sync acquire should win the race in unfair mode');
+
+  pool.release(stolen);
+  assert.equal(await waiter, held, 'Waiter should be served by the next release');
+
+  pool.release(held);
+  pool.destroy();
+});
+
+test('Fairness - ObjectPool serves async waiters in FIFO order', async () => {
+  const pool = createPool({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ id: 0 }),
+  });
+  assert.equal(pool.isFair(), true);
+
+  const held = pool.acquire()!;
+  const order: number[] = [];
+
+  const waiters = Array.from({ length: 5 }, (_, i) =>
+    pool.acquireAsync(1000).then((res) => {
+      order.push(i);
+      pool.release(res);
+    }),
+  );
+
+  pool.release(held);
+  await Promise.all(waiters);
+
+  assert.deepEqual(order, [0, 1, 2, 3, 4], 'Waiters should be served in arrival order');
+
+  await pool.destroy();
+});
+
+test('Fairness - interleaved sync and async acquisitions never exceed capacity', async () => {
+  for (const fair of [true, false]) {
+    const capacity = 3;
+    const pool = createEnginePool(capacity, { fair });
+    const held = new Set<number>();
+    let peak = 0;
+
+    const take = (idx: number) => {
+      assert.ok(!held.has(idx), `Slot ${idx} handed out twice (fair: ${fair})`);
+      held.add(idx);
+      peak = Math.max(peak, held.size);
+    };
+    const giveBack = (idx: number) => {
+      held.delete(idx);
+      pool.release(idx);
+    };
+
+    // Both paths draw from the same stack, so whichever runs is arbitrated by it
+    const tasks: Promise<void>[] = [];
+    for (let i = 0; i < 200; i++) {
+      if (i % 3 === 0) {
+        const idx = pool.acquire();
+        if (idx !== -1) {
+          take(idx);
+          setImmediate(() => giveBack(idx));
+        }
+      } else {
+        tasks.push(
+          pool.acquireAsync(2000).then(async (idx) => {
+            take(idx);
+            await new Promise((resolve) => setImmediate(resolve));
+            giveBack(idx);
+          }),
+        );
+      }
+      if (i % 10 === 0) await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    await Promise.all(tasks);
+    await new Promise((resolve) => setImmediate(resolve));
+
+    assert.ok(peak <= capacity, `Peak ${peak} exceeded capacity (fair: ${fair})`);
+    assert.equal(pool.available, capacity, 'Every slot should be back');
+    assert.equal(pool.pendingCount, 0);
+    pool.destroy();
+  }
+});
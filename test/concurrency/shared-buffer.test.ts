@@ -0,0 +1,402 @@
+import { test } from 'node:test';
+import assert from 'node:assert';
+import path from 'node:path';
+import { MessageChannel, Worker } from 'node:worker_threads';
+import { createEnginePool, createPool, EnginePool, FORMAT_VERSION } from '../../src/index';
+import {
+  BasePool,
+  OFFSET_COUNT,
+  OFFSET_HEAD,
+  OFFSET_NEXT_START,
+  OFFSET_NOTIFY,
+  OFFSET_VERSION,
+} from '../../src/internal/base-pool';
+
+type AcquireResult = { acquired: number[]; available: number; inUse: number };
+type StressResult = { acquired: number; doubleAllocations: number };
+type FuzzResult = { doubleAllocations: number; overReleases: number };
+
+function runWorker<R>(file: string, workerData: object): Promise<R> {
+  return new Promise((resolve, reject) => {
+    const worker = new Worker(path.join(__dirname, 'workers', file), { workerData });
+    worker.once('message', resolve);
+    worker.once('error', reject);
+  });
+}
+
+function runAcquireWorker(buffer: SharedArrayBuffer, count: number): Promise<AcquireResult> {
+  return runWorker('acquire-worker.ts', { buffer, count });
+}
+
+test('SharedBuffer - available and inUse reflect acquisitions from other threads', async () => {
+  const pool = new BasePool(8);
+  for (let i = 0; i < 8; i++) pool.release(i);
+
+  const results = await Promise.all([runAcquireWorker(pool.getBuffer(), 2), runAcquireWorker(pool.getBuffer(), 3)]);
+
+  const acquired = results.flatMap((r) => r.acquired);
+  assert.equal(acquired.length, 5);
+  assert.equal(new Set(acquired).size, 5, 'Each worker should receive distinct slots');
+  assert.ok(!acquired.includes(-1), 'All acquisitions should succeed');
+
+  assert.equal(pool.availableCount(), 3, 'Main thread should see the remaining free slots');
+  assert.equal(pool.inUseCount(), 5, 'Main thread should see slots held by workers');
+
+  pool.destroy();
+});
+
+test('SharedBuffer - attach round-trips a pool buffer', () => {
+  const pool = createEnginePool(4);
+  const held = pool.acquire();
+
+  const attached = EnginePool.attach(pool.getBuffer());
+  assert.equal(attached.size, 4, 'Capacity should be read from the buffer header');
+  assert.equal(attached.available, 3, 'Attached pool should share slot state');
+
+  const idx = attached.acquire();
+  assert.notEqual(idx, held, 'Attached pool should not hand out a held slot');
+  assert.equal(pool.available, 2, 'Acquire through the attached pool should be visible to the owner');
+
+  attached.release(idx);
+  pool.release(held);
+  pool.destroy();
+});
+
+test('SharedBuffer - attach rejects buffers without a pool header', () => {
+  const garbage = new SharedArrayBuffer(64);
+  new Int32Array(garbage).fill(0x1234);
+  assert.throws(() => EnginePool.attach(garbage), /magic value mismatch/);
+
+  assert.throws(() => EnginePool.attach(new SharedArrayBuffer(6)), /unexpected size/);
+  assert.throws(() => BasePool.attach(new SharedArrayBuffer(0)), /unexpected size/);
+
+  // Valid magic but a capacity that doesn't match the buffer length
+  const truncated = new BasePool(8).getBuffer().slice(0, 44);
+  assert.throws(() => BasePool.attach(truncated), /capacity 8 does not match buffer size/);
+});
+
+test('SharedBuffer - attach accepts the current format version', () => {
+  const pool = createEnginePool(2);
+  assert.equal(EnginePool.bufferVersion(pool.getBuffer()), FORMAT_VERSION);
+  assert.doesNotThrow(() => EnginePool.attach(pool.getBuffer()));
+  pool.destroy();
+});
+
+test('SharedBuffer - attach rejects unknown format versions', () => {
+  const buffer = new BasePool(2).getBuffer();
+  const header = new Int32Array(buffer);
+
+  // The version word directly follows the magic value
+  header[OFFSET_VERSION] = FORMAT_VERSION + 1;
+  assert.equal(EnginePool.bufferVersion(buffer), FORMAT_VERSION + 1);
+  const expected = `Unsupported pool buffer version ${FORMAT_VERSION + 1} (expected ${FORMAT_VERSION})`;
+  assert.throws(() => EnginePool.attach(buffer), (err: Error) => err.message === expected);
+
+  header[OFFSET_VERSION] = -7;
+  assert.throws(() => EnginePool.attach(buffer), /Unsupported pool buffer version -7/);
+});
+
+test('SharedBuffer - lock-free stack never hands a slot to two threads at once', async () => {
+  const capacity = 4;
+  const pool = new BasePool(capacity);
+  for (let i = 0; i < capacity; i++) pool.release(i);
+  const claims = new SharedArrayBuffer(capacity * 4);
+
+  const workerData = { buffer: pool.getBuffer(), claims, iterations: 50000 };
+  const results = await Promise.all(
+    Array.from({ length: 8 }, () => runWorker<StressResult>('stress-worker.ts', workerData)),
+  );
+
+  assert.ok(results.reduce((sum, r) => sum + r.acquired, 0) > 0, 'Workers should make progress');
+  assert.equal(results.reduce((sum, r) => sum + r.doubleAllocations, 0), 0, 'No slot should be held by two threads');
+
+  assert.equal(pool.availableCount(), capacity, 'All slots should be back on the stack');
+  assert.equal(pool.inUseCount(), 0, 'Counters should converge once every worker is done');
+  assert.deepEqual(pool.availableSlots().sort(), [0, 1, 2, 3], 'Slot states should agree with the count');
+  const slots = new Set<number>();
+  for (let i = 0; i < capacity; i++) slots.add(pool.acquire());
+  assert.deepEqual([...slots].sort(), [0, 1, 2, 3], 'Stack should hold each slot exactly once');
+  assert.equal(pool.acquire(), -1);
+});
+
+test('SharedBuffer - random interleavings keep the stack and counters consistent', async () => {
+  const capacity = 6;
+  const pool = new BasePool(capacity);
+  for (let i = 0; i < capacity; i++) pool.release(i);
+  const claims = new SharedArrayBuffer(capacity * 4);
+  // Set FUZZ_SEED to replay a failing run
+  const seed = Number(process.env.FUZZ_SEED) || 0x5eed;
+
+  const results = await Promise.all(
+    Array.from({ length: 6 }, (_, i) =>
+      runWorker<FuzzResult>('fuzz-worker.ts', { buffer: pool.getBuffer(), claims, iterations: 20000, seed: seed + i }),
+    ),
+  );
+
+  const failure = `(FUZZ_SEED=${seed})`;
+  assert.equal(results.reduce((sum, r) => sum + r.doubleAllocations, 0), 0, `No slot held twice ${failure}`);
+  assert.equal(results.reduce((sum, r) => sum + r.overReleases, 0), 0, `No release was rejected ${failure}`);
+  assert.doesNotThrow(() => pool.verify(), `Stack links and count agree ${failure}`);
+  assert.equal(pool.availableCount(), capacity, failure);
+  assert.equal(pool.stackLength(), capacity, failure);
+  assert.deepEqual(pool.availableSlots(), [0, 1, 2, 3, 4, 5], failure);
+});
+
+test('SharedBuffer - payloads written in one thread are readable in another', async () => {
+  const pool = createEnginePool(4);
+  const held = pool.acquire();
+  pool.setPayload(held, 0xdeadbeef);
+
+  const { idx, parentPayload } = await runWorker<{ idx: number; parentPayload: number }>('payload-worker.ts', {
+    buffer: pool.getBuffer(),
+    value: 4242,
+    parentSlot: held,
+  });
+
+  assert.equal(parentPayload, 0xdeadbeef, 'Worker should read the payload set by the main thread');
+  assert.notEqual(idx, held);
+  assert.equal(pool.getPayload(idx), 4242, 'Main thread should read the payload set by the worker');
+
+  pool.release(idx);
+  pool.release(held);
+  pool.destroy();
+});
+
+test('SharedBuffer - a stale token cannot release a slot another thread holds now', async () => {
+  const pool = createEnginePool(1);
+  const first = pool.acquireWithToken()!;
+  pool.releaseWithToken(first.slot, first.token);
+
+  const worker = new Worker(path.join(__dirname, 'workers', 'token-worker.ts'), {
+    workerData: { buffer: pool.getBuffer() },
+  });
+  const next = () => new Promise<{ type: string; slot?: number; token?: number }>((r) => worker.once('message', r));
+  const held = await next();
+  assert.equal(held.slot, first.slot, 'The worker got the same slot');
+  assert.notEqual(held.token, first.token, 'Under a new generation');
+
+  assert.throws(() => pool.releaseWithToken(first.slot, first.token), {
+    name: 'PoolError',
+    kind: 'notCheckedOut',
+    slot: first.slot,
+  });
+  assert.equal(pool.isCheckedOut(first.slot), true, "The worker's checkout is untouched");
+
+  worker.postMessage('release');
+  assert.equal((await next()).type, 'released');
+  assert.equal(pool.availableCount(), 1);
+  assert.throws(() => pool.releaseWithToken(first.slot, held.token!), /not checked out/, 'Tokens are single-use');
+  pool.destroy();
+});
+
+test('SharedBuffer - a wake-up that finds the stack empty keeps the waiter queued', async () => {
+  const pool = createEnginePool(1);
+  const other = EnginePool.attach(pool.getBuffer());
+  const held = other.acquire();
+  const waiter = pool.acquireAsync(1000);
+
+  // Wake the watcher the way another thread's release would, but with nothing pushed
+  const header = new Int32Array(pool.getBuffer());
+  Atomics.add(header, OFFSET_NOTIFY, 1);
+  Atomics.notify(header, OFFSET_NOTIFY);
+  await new Promise((resolve) => setTimeout(resolve, 20));
+  assert.equal(pool.pendingCount, 1, 'The waiter neither failed nor left the queue');
+
+  other.release(held);
+  assert.equal(await waiter, held, 'It is served by the next real release');
+  pool.release(held);
+  pool.destroy();
+});
+
+test('SharedBuffer - payload access validates slot and value', () => {
+  const pool = createEnginePool(2);
+
+  assert.equal(pool.getPayload(1), 0, 'Payloads start at 0');
+  assert.throws(() => pool.getPayload(2), /Slot 2 is out of range \(capacity 2\)/);
+  assert.throws(() => pool.setPayload(-1, 1), /Slot -1 is out of range/);
+  assert.throws(() => pool.setPayload(0, 2 ** 32), /Payload must be a u32/);
+  assert.throws(() => pool.setPayload(0, -1), /Payload must be a u32/);
+
+  pool.setPayload(0, 0xffffffff);
+  assert.equal(pool.getPayload(0), 0xffffffff, 'Full u32 range should round-trip');
+
+  pool.destroy();
+});
+
+test('SharedBuffer - acquireBlocking wakes when another thread releases', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  const worker = new Worker(path.join(__dirname, 'workers', 'blocking-worker.ts'), {
+    workerData: { buffer: pool.getBuffer(), timeoutMs: 5000 },
+  });
+
+  const result = await new Promise<{ idx: number; waitedMs: number }>((resolve, reject) => {
+    worker.on('message', (msg) => {
+      if (msg.type === 'blocking') setTimeout(() => pool.release(held), 50);
+      else resolve(msg);
+    });
+    worker.once('error', reject);
+  });
+
+  assert.equal(result.idx, held, 'Blocked worker should receive the released slot');
+  assert.ok(result.waitedMs >= 30, `Worker should have been parked (waited ${result.waitedMs}ms)`);
+  assert.ok(result.waitedMs < 5000, 'Worker should wake before its timeout');
+  assert.equal(pool.available, 0);
+});
+
+test('SharedBuffer - acquireBlocking returns -1 after the timeout', () => {
+  const pool = createEnginePool(1);
+  pool.acquire();
+
+  const start = Date.now();
+  assert.equal(pool.acquireBlocking(50), -1);
+  assert.ok(Date.now() - start >= 45, 'Should wait for roughly the timeout');
+
+  pool.destroy();
+});
+
+test('SharedBuffer - verify passes on a healthy buffer', async () => {
+  const pool = createEnginePool(4);
+  assert.equal(pool.verify(), true);
+
+  const a = pool.acquire();
+  const b = pool.acquire();
+  pool.verify();
+
+  pool.release(a);
+  pool.release(b);
+  await runAcquireWorker(pool.getBuffer(), 2);
+  pool.verify();
+
+  pool.destroy();
+  pool.verify();
+});
+
+test('SharedBuffer - verify is inconclusive rather than failing while the stack keeps moving', () => {
+  const pool = createEnginePool(4);
+  const buffer = pool.getBuffer();
+
+  // Every read of HEAD sees a new tag, as if other threads pushed and popped between each load
+  const load = Atomics.load;
+  let reads = 0;
+  const moving = (array: Int32Array, index: number) =>
+    array.buffer === buffer && index === OFFSET_HEAD ? load(array, index) + (++reads << 16) : load(array, index);
+  (Atomics as { load: typeof moving }).load = moving;
+  try {
+    assert.equal(pool.verify(), false);
+    assert.equal(pool.pooledLength(), 4, 'The last walk is still reported');
+    assert.equal(pool.slotsInUse(), null, 'An unsettled walk is not passed off as a consistent bitmap');
+  } finally {
+    (Atomics as { load: typeof load }).load = load;
+  }
+  assert.equal(pool.verify(), true);
+  pool.destroy();
+});
+
+test('SharedBuffer - verify reports injected corruption', () => {
+  // Slots 0..3 are released in order, so the stack reads 3 -> 2 -> 1 -> 0
+  let pool = createEnginePool(4);
+  let state = new Int32Array(pool.getBuffer());
+  state[OFFSET_NEXT_START + 3] = 99;
+  assert.throws(() => pool.verify(), /Pool buffer corrupted: slot index 99 out of range \(capacity 4\)/);
+
+  pool = createEnginePool(4);
+  state = new Int32Array(pool.getBuffer());
+  state[OFFSET_NEXT_START + 0] = 3;
+  assert.throws(() => pool.verify(), /Pool buffer corrupted: cycle detected at slot 3/);
+
+  pool = createEnginePool(4);
+  state = new Int32Array(pool.getBuffer());
+  state[OFFSET_COUNT] = 2;
+  assert.throws(() => pool.verify(), /Pool buffer corrupted: 4 slots reachable but count is 2/);
+});
+
+test('SharedBuffer - ObjectPool cannot be sent to another thread', async () => {
+  // Resources are plain JS objects owned by the creating thread; only EnginePool buffers are shareable
+  const pool = createPool({ min: 1, max: 1, resourceFactory: () => ({ id: 0 }) });
+  const { port1, port2 } = new MessageChannel();
+
+  assert.throws(() => port1.postMessage(pool), { name: 'DataCloneError' });
+  assert.throws(() => structuredClone(pool), { name: 'DataCloneError' });
+
+  port1.close();
+  port2.close();
+  await pool.destroy();
+});
+
+test('SharedBuffer - handles attached on the same thread share acquisition accounting', async () => {
+  const owner = createEnginePool(3);
+  const handle = EnginePool.attach(owner.getBuffer());
+
+  const viaHandle = handle.acquire();
+  const viaOwner = owner.acquire();
+  assert.equal(owner.inUse, 2);
+  assert.equal(handle.inUse, 2, 'Both handles should read the same counters');
+
+  // Release through the other handle than the one that acquired
+  owner.release(viaHandle);
+  handle.release(viaOwner);
+  assert.equal(owner.available, 3);
+  assert.equal(handle.available, 3);
+  owner.verify();
+
+  // A waiter queued on one handle is woken by a release through the other
+  const held = [owner.acquire(), owner.acquire(), owner.acquire()];
+  const waiter = owner.acquireAsync(2000);
+  assert.equal(owner.pendingCount, 1);
+  assert.equal(handle.pendingCount, 0, 'Waiter queues are per handle');
+
+  handle.release(held[0]);
+  assert.equal(await waiter, held[0], 'Owner waiter should receive the slot released via the attached handle');
+  assert.equal(handle.available, 0);
+
+  for (const idx of held) handle.release(idx);
+  assert.equal(owner.available, 3);
+  owner.verify();
+  owner.destroy();
+});
+
+test('SharedBuffer - getPayloads reads a batch of slots', async () => {
+  const pool = createEnginePool(4);
+  const slots = await pool.acquireManyAsync(3);
+  slots.forEach((idx, i) => pool.setPayload(idx, 100 + i));
+
+  assert.deepEqual(pool.getPayloads(slots), [100, 101, 102]);
+  assert.deepEqual(pool.getPayloads([...slots].reverse()), [102, 101, 100], 'Order should follow the input');
+  assert.deepEqual(pool.getPayloads([]), []);
+  assert.throws(() => pool.getPayloads([slots[0], 4]), /Slot 4 is out of range \(capacity 4\)/);
+
+  slots.forEach((idx) => pool.release(idx));
+  pool.destroy();
+});
+
+test('SharedBuffer - slotsInUse marks exactly the slots held by other threads', async () => {
+  const pool = createEnginePool(12);
+  const results = await Promise.all([runAcquireWorker(pool.getBuffer(), 3), runAcquireWorker(pool.getBuffer(), 4)]);
+  const held = results.flatMap((r) => r.acquired);
+
+  const bitmap = pool.slotsInUse()!;
+  assert.equal(bitmap.length, 2, 'One bit per slot, rounded up to whole bytes');
+  const marked: number[] = [];
+  for (let i = 0; i < 16; i++) if (bitmap[i >> 3] & (1 << (i & 7))) marked.push(i);
+  assert.deepEqual(marked, [...held].sort((a, b) => a - b));
+
+  held.forEach((idx) => pool.release(idx));
+  assert.deepEqual([...pool.slotsInUse()!], [0, 0]);
+  pool.destroy();
+});
+
+test('SharedBuffer - pooledLength tracks the stack and exposes counter drift', async () => {
+  const pool = createEnginePool(6);
+  await runAcquireWorker(pool.getBuffer(), 2);
+  pool.acquire();
+  assert.equal(pool.pooledLength(), 3);
+  assert.equal(pool.pooledLength(), pool.availableCount(), 'A healthy pool agrees with its counter');
+
+  // Simulated fault: the shared counter is bumped without pushing anything
+  new Int32Array(pool.getBuffer())[OFFSET_COUNT] += 2;
+  assert.equal(pool.availableCount(), 5);
+  assert.equal(pool.pooledLength(), 3, 'The stack itself is unchanged');
+  pool.destroy();
+});
@@ -0,0 +1,11 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { BasePool } from '../../../src/internal/base-pool';
+
+// Attaches to the parent's buffer and acquires `count` slots without releasing them
+const pool = new BasePool(workerData.buffer as SharedArrayBuffer);
+const acquired: number[] = [];
+for (let i = 0; i < (workerData.count as number); i++) {
+  acquired.push(pool.acquire());
+}
+
+parentPort!.postMessage({ acquired, available: pool.availableCount(), inUse: pool.inUseCount() });
@@ -0,0 +1,10 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { EnginePool } from '../../../src/index';
+
+// Parks in acquireBlocking until the parent releases a slot
+const pool = EnginePool.attach(workerData.buffer as SharedArrayBuffer);
+parentPort!.postMessage({ type: 'blocking' });
+
+const start = Date.now();
+const idx = pool.acquireBlocking(workerData.timeoutMs as number);
+parentPort!.postMessage({ type: 'acquired', idx, waitedMs: Date.now() - start });
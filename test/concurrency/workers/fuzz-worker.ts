@@ -0,0 +1,44 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { BasePool } from '../../../src/internal/base-pool';
+
+// Runs a seeded random mix of acquire/release/releaseBack/acquireFrom against a shared pool,
+// holding several slots at a time. `claims` catches a slot handed to two holders at once.
+const pool = BasePool.attach(workerData.buffer as SharedArrayBuffer);
+const claims = new Int32Array(workerData.claims as SharedArrayBuffer);
+const capacity = pool.getCapacity();
+
+// mulberry32: small, fast and good enough to make a failing seed reproducible
+let seed = workerData.seed as number;
+const random = () => {
+  seed = (seed + 0x6d2b79f5) | 0;
+  let t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+  t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+  return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+};
+
+const held: number[] = [];
+let doubleAllocations = 0;
+const claim = (idx: number) => {
+  if (idx === -1) return;
+  if (Atomics.add(claims, idx, 1) !== 0) doubleAllocations++;
+  held.push(idx);
+};
+const giveBack = (toBack: boolean) => {
+  if (held.length === 0) return;
+  const idx = held.splice(Math.floor(random() * held.length), 1)[0];
+  // Drop the claim before the slot becomes visible to other threads
+  Atomics.sub(claims, idx, 1);
+  if (toBack) pool.releaseBack(idx);
+  else pool.release(idx);
+};
+
+for (let i = 0; i < (workerData.iterations as number); i++) {
+  const op = random();
+  if (op < 0.4) claim(pool.acquire());
+  else if (op < 0.55) claim(pool.acquireFrom([Math.floor(random() * capacity), Math.floor(random() * capacity)]));
+  else if (op < 0.85) giveBack(false);
+  else giveBack(true);
+}
+while (held.length > 0) giveBack(false);
+
+parentPort!.postMessage({ doubleAllocations, overReleases: pool.overReleaseCount() });
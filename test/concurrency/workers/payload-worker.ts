@@ -0,0 +1,9 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { EnginePool } from '../../../src/index';
+
+// Acquires a slot, tags it with a payload and reports back what the parent stored earlier
+const pool = EnginePool.attach(workerData.buffer as SharedArrayBuffer);
+const idx = pool.acquire();
+pool.setPayload(idx, workerData.value as number);
+
+parentPort!.postMessage({ idx, parentPayload: pool.getPayload(workerData.parentSlot as number) });
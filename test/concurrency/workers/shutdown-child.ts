@@ -0,0 +1,33 @@
+import { createEnginePool, createPool } from '../../../src/index';
+import type { PoolError } from '../../../src/index';
+
+// Runs in its own process: every acquire below waits forever unless the pools give up on exit
+const engine = createEnginePool(1);
+const slot = engine.acquire();
+engine.unref();
+
+const destroyed: number[] = [];
+const objects = createPool({
+  min: 1,
+  max: 1,
+  resourceFactory: () => ({ id: 0 }),
+  resourceDestroyer: (res) => {
+    destroyed.push(res.id);
+  },
+});
+const held = objects.acquire()!;
+objects.unref();
+
+// Only the waiters are rejected: what is held can still go back, and the pool still owns its cleanup
+engine.acquireAsync().catch((error: PoolError) => {
+  engine.release(slot);
+  const idle = engine.available === 1;
+  engine.destroy();
+  console.log(JSON.stringify({ pool: 'engine', kind: error.kind, message: error.message, idle }));
+});
+objects.acquireAsync().catch(async (error: PoolError) => {
+  objects.release(held);
+  const idle = objects.getMetrics().busy === 0;
+  await objects.destroy();
+  console.log(JSON.stringify({ pool: 'object', kind: error.kind, message: error.message, idle, destroyed }));
+});
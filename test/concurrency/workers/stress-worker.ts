@@ -0,0 +1,21 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { BasePool } from '../../../src/internal/base-pool';
+
+// Hammers a shared pool with acquire/release, using `claims` to detect a slot held by two threads at once
+const pool = BasePool.attach(workerData.buffer as SharedArrayBuffer);
+const claims = new Int32Array(workerData.claims as SharedArrayBuffer);
+const iterations = workerData.iterations as number;
+
+let acquired = 0;
+let doubleAllocations = 0;
+for (let i = 0; i < iterations; i++) {
+  const idx = pool.acquire();
+  if (idx === -1) continue;
+
+  acquired++;
+  if (Atomics.add(claims, idx, 1) !== 0) doubleAllocations++;
+  Atomics.sub(claims, idx, 1);
+  pool.release(idx);
+}
+
+parentPort!.postMessage({ acquired, doubleAllocations });
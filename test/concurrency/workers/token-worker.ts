@@ -0,0 +1,13 @@
+import { parentPort, workerData } from 'node:worker_threads';
+import { EnginePool } from '../../../src/index';
+
+// Holds a slot with a token until the parent asks for it back
+const pool = EnginePool.attach(workerData.buffer as SharedArrayBuffer);
+const { slot, token } = pool.acquireWithToken()!;
+parentPort!.postMessage({ type: 'acquired', slot, token });
+
+parentPort!.once('message', () => {
+  pool.releaseWithToken(slot, token);
+  parentPort!.postMessage({ type: 'released' });
+  parentPort!.close();
+});
@@ -291,3 +291,23 @@ test('Edge case - Dynamic pool with min equal to max after creation', async () =
 
   await pool.destroy();
 });
+
+test('Edge case - acquire skips a slot whose resource was removed while available', async () => {
+  const pool = createPool({ min: 3, max: 3, resourceFactory: () => ({ id: Math.random() }) });
+  const internals = pool as unknown as { resources: ({ id: number } | null)[] };
+
+  // Simulate a removal racing with acquisition: the top slot is still on the stack but empty
+  const top = pool.acquire()!;
+  pool.release(top);
+  const topSlot = internals.resources.indexOf(top);
+  internals.resources[topSlot] = null;
+
+  const res = pool.acquire();
+  assert.ok(res, 'Acquire should fall through to another free resource');
+  assert.notStrictEqual(res, top);
+  assert.strictEqual(await pool.acquireAsync(100), internals.resources.find((r) => r && r !== res));
+  assert.equal(pool.acquire(), null, 'Only two usable resources remain');
+  assert.equal(pool.getMetrics().size, 2, 'The empty slot should be available for creation again');
+
+  await pool.destroy();
+});
@@ -0,0 +1,73 @@
+import { test } from 'node:test';
+import assert from 'node:assert';
+import { createEnginePool, createPool } from '../../src/index';
+
+test('Observer - EnginePool reports acquire, timeout and release in order', async () => {
+  const events: string[] = [];
+  const pool = createEnginePool(1, {
+    observer: {
+      onAcquire: (idx) => events.push(`acquire:${idx}`),
+      onRelease: (idx) => events.push(`release:${idx}`),
+      onTimeout: (ms) => events.push(`timeout:${ms}`),
+    },
+  });
+
+  const idx = await pool.acquireAsync();
+  await assert.rejects(() => pool.acquireAsync(20), /Timeout/);
+  assert.equal(pool.acquireBlocking(10), -1);
+  pool.release(idx);
+
+  assert.deepEqual(events, ['acquire:0', 'timeout:20', 'timeout:10', 'release:0']);
+  pool.destroy();
+});
+
+test('Observer - ObjectPool reports acquire, timeout and release with the resource', async () => {
+  const counts = { acquire: 0, release: 0, timeout: 0 };
+  const seen: object[] = [];
+  const pool = createPool({
+    min: 1,
+    max: 1,
+    resourceFactory: () => ({ id: 1 }),
+    observer: {
+      onAcquire: (res) => {
+        counts.acquire++;
+        seen.push(res);
+      },
+      onRelease: (res) => {
+        counts.release++;
+        seen.push(res);
+      },
+      onTimeout: () => counts.timeout++,
+    },
+  });
+
+  const res = pool.acquire()!;
+  await assert.rejects(() => pool.acquireAsync(20), /Timeout/);
+  pool.release(res);
+  await pool.use(() => {});
+
+  assert.deepEqual(counts, { acquire: 2, release: 2, timeout: 1 });
+  assert.ok(seen.every((r) => r === res), 'Hooks should receive the pooled resource');
+
+  await pool.destroy();
+});
+
+test('Observer - onTimeout fires for every timed-out acquisition on a saturated pool', async () => {
+  const timeouts: { timeoutMs: number; waitedMs: number; pending: number }[] = [];
+  const pool = createEnginePool(1, {
+    observer: { onTimeout: (timeoutMs, info) => timeouts.push({ timeoutMs, ...info }) },
+  });
+
+  const idx = pool.acquire();
+  const waiters = [20, 20, 40].map((ms) => assert.rejects(pool.acquireAsync(ms), /Timeout/));
+  await Promise.all(waiters);
+
+  assert.equal(timeouts.length, 3, 'Each timeout should be reported');
+  assert.deepEqual(timeouts.map((t) => t.timeoutMs), [20, 20, 40]);
+  assert.ok(timeouts.every((t) => t.waitedMs >= t.timeoutMs - 2), 'waitedMs should cover the timeout');
+  assert.equal(timeouts[0].pending, 2, 'The other waiters are still queued');
+  assert.equal(timeouts[2].pending, 0);
+
+  pool.release(idx);
+  pool.destroy();
+});
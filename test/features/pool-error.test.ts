@@ -0,0 +1,52 @@
+import { test } from 'node:test';
+import assert from 'node:assert';
+import { createEnginePool, createPool, PoolError } from '../../src/index';
+import type { PoolErrorKind } from '../../src/index';
+import { OFFSET_COUNT } from '../../src/internal/base-pool';
+
+const isPoolError = (kind: PoolErrorKind, extra: Partial<PoolError> = {}) => (err: unknown) => {
+  assert.ok(err instanceof PoolError, `Expected a PoolError, got ${err}`);
+  assert.ok(err instanceof Error);
+  assert.equal(err.name, 'PoolError');
+  assert.equal(err.kind, kind);
+  for (const [key, value] of Object.entries(extra)) assert.equal(err[key as keyof PoolError], value);
+  return true;
+};
+
+test('PoolError - EnginePool failures carry a kind', async () => {
+  const pool = createEnginePool(1);
+  const held = pool.acquire();
+
+  await assert.rejects(pool.acquireAsync(20), isPoolError('timeout', { timeoutMs: 20 }));
+  await assert.rejects(pool.acquireManyAsync(1, 20), isPoolError('timeout', { timeoutMs: 20 }));
+  assert.throws(() => pool.getPayload(5), isPoolError('invalidSlot', { slot: 5 }));
+
+  pool.releaseChecked(held);
+  assert.throws(() => pool.releaseChecked(held), isPoolError('notCheckedOut', { slot: held }));
+
+  new Int32Array(pool.getBuffer())[OFFSET_COUNT] = 0;
+  assert.throws(() => pool.verify(), isPoolError('corrupted'));
+
+  pool.destroy();
+  await assert.rejects(pool.acquireAsync(), isPoolError('closed'));
+});
+
+test('PoolError - ObjectPool failures carry a kind', async () => {
+  const pool = createPool({ min: 1, max: 1, resourceFactory: () => ({ id: 0 }) });
+  const res = pool.acquire()!;
+
+  await assert.rejects(pool.acquireAsync(20), isPoolError('timeout', { timeoutMs: 20 }));
+  assert.throws(() => pool.add({ id: 1 }), isPoolError('full'));
+  assert.throws(() => pool.intoInner(), isPoolError('inUse'));
+
+  pool.release(res);
+  await pool.destroy();
+  assert.throws(() => pool.requestSize(1), isPoolError('closed'));
+});
+
+test('PoolError - argument validation still throws a plain Error', () => {
+  assert.throws(
+    () => createPool({ min: 2, max: 1, resourceFactory: () => ({}) }),
+    (err: Error) => !(err instanceof PoolError) && /max must be >= min/.test(err.message),
+  );
+});
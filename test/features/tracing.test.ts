@@ -0,0 +1,56 @@
+import { test } from 'node:test';
+import assert from 'node:assert';
+import diagnosticsChannel from 'node:diagnostics_channel';
+import { ACQUIRE_CHANNEL, createEnginePool, createPool } from '../../src/index';
+import type { AcquireTrace } from '../../src/index';
+
+function captureTraces() {
+  const traces: AcquireTrace[] = [];
+  const listener = (message: unknown) => traces.push(message as AcquireTrace);
+  diagnosticsChannel.subscribe(ACQUIRE_CHANNEL, listener);
+  return { traces, stop: () => diagnosticsChannel.unsubscribe(ACQUIRE_CHANNEL, listener) };
+}
+
+test('Tracing - EnginePool publishes the outcome of each async acquisition', async () => {
+  const { traces, stop } = captureTraces();
+  const pool = createEnginePool(1);
+
+  const idx = await pool.acquireAsync(100);
+  await assert.rejects(pool.acquireAsync(20), /Timeout/);
+  const closing = assert.rejects(pool.acquireAsync(), /Pool is destroyed/);
+  pool.destroy();
+  await closing;
+  stop();
+
+  assert.deepEqual(
+    traces.map(({ pool, timeoutMs, outcome }) => ({ pool, timeoutMs, outcome })),
+    [
+      { pool: 'EnginePool', timeoutMs: 100, outcome: 'acquired' },
+      { pool: 'EnginePool', timeoutMs: 20, outcome: 'timeout' },
+      { pool: 'EnginePool', timeoutMs: 0, outcome: 'closed' },
+    ],
+  );
+  assert.ok(traces[1].waitedMs >= 19, 'waitedMs should cover the wait');
+  pool.release(idx);
+});
+
+test('Tracing - ObjectPool publishes acquisitions and stays silent without subscribers', async () => {
+  const pool = createPool({ min: 1, max: 1, resourceFactory: () => ({ id: 0 }) });
+  await pool.use(() => {});
+
+  const { traces, stop } = captureTraces();
+  const res = await pool.acquireAsync();
+  await assert.rejects(pool.acquireAsync(20), /Timeout/);
+  stop();
+  pool.release(res);
+  await pool.use(() => {});
+
+  assert.deepEqual(
+    traces.map((t) => [t.pool, t.outcome]),
+    [
+      ['ObjectPool', 'acquired'],
+      ['ObjectPool', 'timeout'],
+    ],
+  );
+  await pool.destroy();
+});
@@ -292,3 +292,73 @@ test('Feature - Destroyer errors propagate on destroy', async () => {
     },
   );
 });
+
+test('Feature - Bubbled validation error returns the slot to the pool', async () => {
+  let counter = 0;
+
+  const config = {
+    min: 1,
+    max: 1,
+    resourceFactory: async () => ({
+      id: counter++,
+      healthy: true,
+      destroyed: false,
+    }),
+    validateResource: async () => {
+      throw new Error('Validation crashed');
+    },
+    bubbleValidationErrors: true,
+  };
+
+  const pool = createPool(config);
+
+  await assert.rejects(() => pool.acquireAsync(1000), (err: Error) => err.message.includes('Validation crashed'));
+
+  const metrics = pool.getMetrics();
+  assert.equal(metrics.available, 1, 'Slot should be released after the validation error');
+  assert.equal(metrics.busy, 0, 'No slot should be left checked out');
+
+  const res = pool.acquire();
+  assert(res !== null, 'Resource should still be acquirable');
+
+  pool.release(res);
+  await pool.destroy();
+});
+
+test('Feature - Failed replacement does not leak the slot', async () => {
+  let factoryCalls = 0;
+
+  const config = {
+    min: 1,
+    max: 1,
+    resourceFactory: async () => {
+      factoryCalls++;
+      if (factoryCalls === 2) {
+        throw new Error('Factory failed');
+      }
+      return {
+        id: factoryCalls,
+        healthy: factoryCalls > 1,
+        destroyed: false,
+      };
+    },
+    validateResource: async (resource: TestResource) => resource.healthy,
+  };
+
+  const pool = createPool(config);
+
+  // First resource is unhealthy and its replacement fails
+  await assert.rejects(() => pool.acquireAsync(1000), (err: Error) => err.message.includes('Factory failed'));
+
+  const metrics = pool.getMetrics();
+  assert.equal(metrics.size, 0, 'Empty slot should no longer count as an active resource');
+  assert.equal(metrics.busy, 0, 'Empty slot should not stay checked out');
+
+  // The slot is recreated on demand
+  const res = await pool.acquireAsync(1000);
+  assert(res.healthy, 'Should acquire a freshly created resource');
+  assert.equal(factoryCalls, 3, 'Factory should be called again for the recycled slot');
+
+  pool.release(res);
+  await pool.destroy();
+});